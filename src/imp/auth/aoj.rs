@@ -1,11 +1,213 @@
+use super::SessionStatus;
+use crate::imp::fetch::aoj::AOJ_API_TOP;
+use crate::imp::http::Session;
+use crate::{eprintln_debug, eprintln_warning};
 use anyhow::bail;
 use anyhow::{Context, Result};
+use reqwest::blocking::Response;
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use reqwest::StatusCode;
+use serde_derive::Deserialize;
+use std::sync::Arc;
 
-#[allow(dead_code)]
-pub fn login(_username: String, _password: String) -> Result<String> {
-    bail!("logging in to AOJ is currently not supported");
+const SERVICE_NAME: &str = "aoj";
+
+fn session() -> Result<Arc<Session>> {
+    Session::get(SERVICE_NAME, AOJ_API_TOP).context("failed to load session")
+}
+
+pub fn login(username: &str, password: &str) -> Result<()> {
+    let session = session()?;
+    session.clear();
+    post_session(&session, AOJ_API_TOP, username, password)?;
+    session.save()
+}
+
+/// Creates a session with the API, which answers it with the session cookie.
+fn post_session(session: &Session, api_top: &str, username: &str, password: &str) -> Result<()> {
+    let body = serde_json::json!({ "id": username, "password": password });
+    let res = session
+        .client()
+        .post(format!("{}/session", api_top))
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .context("posting account info failed")?;
+    eprintln_debug!("response: {:?}", res);
+
+    match res.status() {
+        status if status.is_success() => Ok(()),
+        StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED => {
+            bail!("invalid username or password")
+        }
+        status => bail!("unexpected HTTP status: {}", status),
+    }
+}
+
+#[derive(Deserialize)]
+struct User {
+    id: String,
+}
+
+/// Checks whether the saved session is still valid by asking who is logged in.
+pub fn session_status() -> Result<SessionStatus> {
+    if !super::has_session_info(SERVICE_NAME) {
+        return Ok(SessionStatus::NotLoggedIn);
+    }
+
+    let session = session()?;
+    let status = check_self(&session, AOJ_API_TOP)?;
+    save_if_changed(&session);
+
+    Ok(status)
+}
+
+fn check_self(session: &Session, api_top: &str) -> Result<SessionStatus> {
+    let res = get(session, &format!("{}/self", api_top), HeaderMap::new())?;
+    if matches!(
+        res.status(),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+    ) {
+        return Ok(SessionStatus::Expired);
+    }
+
+    let text = res
+        .error_for_status()
+        .context("the server returned an error")?
+        .text()
+        .context("failed to get the text")?;
+    let user: User = serde_json::from_str(&text).context("failed to parse the user")?;
+
+    Ok(SessionStatus::LoggedIn { username: user.id })
+}
+
+pub fn authenticated_get(url: &str, headers: HeaderMap) -> Result<Response> {
+    let session = session()?;
+    let res = get(&session, url, headers)?;
+
+    save_if_changed(&session);
+
+    Ok(res)
+}
+
+/// Keeps the session cookie when it is renewed. Failing to save it does not
+/// fail the request.
+fn save_if_changed(session: &Session) {
+    if let Err(e) = session.save_if_changed() {
+        eprintln_warning!("failed to save the session: {:#}", e);
+    }
 }
 
-pub fn authenticated_get(url: &str) -> Result<reqwest::blocking::Response> {
-    reqwest::blocking::get(url).with_context(|| format!("failed to get {} without login", url))
+fn get(session: &Session, url: &str, headers: HeaderMap) -> Result<Response> {
+    let res = session
+        .client()
+        .get(url)
+        .headers(headers)
+        .send()
+        .with_context(|| format!("failed to get {}", url))?;
+    eprintln_debug!("response: {:?}", res);
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves `count` requests like the AOJ API: `POST /session` accepts
+    /// `user`/`pass` and the other paths need the session cookie.
+    fn serve_mock_api(count: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let api_top = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut cookie = None;
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(": ").unwrap();
+                    match &*name.to_ascii_lowercase() {
+                        "cookie" => cookie = Some(value.to_string()),
+                        "content-length" => content_length = value.parse().unwrap(),
+                        _ => {}
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let body: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+
+                let logged_in = cookie.as_deref() == Some("JSESSIONID=xyz");
+                let (status, extra_header, content) = match request_line.split(' ').nth(1) {
+                    Some("/session") if body["id"] == "user" && body["password"] == "pass" => (
+                        "200 OK",
+                        "Set-Cookie: JSESSIONID=xyz; Path=/; HttpOnly\r\n",
+                        r#"{"id":"user"}"#,
+                    ),
+                    Some("/session") => ("400 Bad Request", "", "[]"),
+                    Some("/self") if logged_in => ("200 OK", "", r#"{"id":"user"}"#),
+                    Some("/resources/descriptions/ja/SECRET") if logged_in => {
+                        ("200 OK", "", r#"{"html":"secret"}"#)
+                    }
+                    _ => ("401 Unauthorized", "", "[]"),
+                };
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\n{}Content-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    extra_header,
+                    content.len(),
+                    content
+                )
+                .unwrap();
+            }
+        });
+
+        api_top
+    }
+
+    #[test]
+    fn test_login() {
+        let api_top = serve_mock_api(6);
+        let session = Session::empty(SERVICE_NAME);
+        let restricted = format!("{}/resources/descriptions/ja/SECRET", api_top);
+
+        assert_eq!(
+            check_self(&session, &api_top).unwrap(),
+            SessionStatus::Expired
+        );
+        assert!(post_session(&session, &api_top, "user", "wrong").is_err());
+        assert_eq!(
+            get(&session, &restricted, HeaderMap::new())
+                .unwrap()
+                .status(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        post_session(&session, &api_top, "user", "pass").unwrap();
+        assert_eq!(
+            check_self(&session, &api_top).unwrap(),
+            SessionStatus::LoggedIn {
+                username: "user".to_string()
+            }
+        );
+        let res = get(&session, &restricted, HeaderMap::new()).unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text().unwrap(), r#"{"html":"secret"}"#);
+    }
 }
@@ -1,118 +1,86 @@
-use crate::eprintln_debug;
+use super::SessionStatus;
+use crate::imp::http::Session;
+use crate::{eprintln_debug, eprintln_warning};
 use anyhow::anyhow;
 use anyhow::{bail, ensure};
 use anyhow::{Context, Result};
-use itertools::Itertools;
+use lazy_static::lazy_static;
 use maplit::hashmap;
-use reqwest::blocking::{Client, ClientBuilder, RequestBuilder, Response};
-use reqwest::header;
-use reqwest::header::GetAll;
-use reqwest::header::HeaderValue;
-use reqwest::redirect::Policy;
+use regex::Regex;
+use reqwest::blocking::Response;
+use reqwest::header::HeaderMap;
 use reqwest::StatusCode;
 use scraper::{Html, Selector};
-use std::collections::HashMap;
+use std::sync::Arc;
 
 const SERVICE_NAME: &str = "atcoder";
+const SESSION_COOKIE: &str = "REVEL_SESSION";
+const ATCODER_TOP: &str = "https://atcoder.jp/";
+const LOGIN_URL: &str = "https://atcoder.jp/login";
 
-struct CookieStore {
-    cookies: HashMap<String, String>,
-}
-
-impl CookieStore {
-    pub fn new() -> CookieStore {
-        CookieStore {
-            cookies: HashMap::new(),
-        }
-    }
-
-    pub fn load_from_session() -> Result<CookieStore> {
-        let cookies = super::load_session_info(SERVICE_NAME)?;
-        let cookies = String::from_utf8_lossy(&cookies).into_owned();
-        let cookies = cookies
-            .lines()
-            .map(|l| l.split('\t').map(ToString::to_string).collect_vec())
-            .map(|e| (e[0].clone(), e[1].clone()))
-            .collect();
-
-        Ok(CookieStore { cookies })
-    }
-
-    pub fn save_to_session(&self) -> Result<()> {
-        let cookies = self
-            .cookies
-            .iter()
-            .map(|(k, v)| format!("{}\t{}", k, v))
-            .join("\n");
-        super::store_session_info(SERVICE_NAME, cookies.as_bytes())?;
-
-        Ok(())
-    }
-
-    pub fn with_cookie(&mut self, req: RequestBuilder) -> Result<Response> {
-        let cookies = self
-            .cookies
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .join(";");
-        let cookies = HeaderValue::from_str(&cookies)?;
-        let req = req.header(header::COOKIE, cookies);
-        let resp = req.send()?;
-        self.update_cookie(resp.headers().get_all(header::SET_COOKIE));
-
-        Ok(resp)
-    }
-
-    fn update_cookie(&mut self, cookie: GetAll<HeaderValue>) {
-        let new_cookies: HashMap<String, String> = cookie
-            .into_iter()
-            .filter_map(|x| x.to_str().ok())
-            .flat_map(|cookiestr| cookiestr.split(';'))
-            .filter_map(|raw_value| {
-                let mut split = raw_value.splitn(2, '=');
-                let name = split.next()?.trim().to_string();
-                let value = split.next()?.trim().to_string();
-                eprintln_debug!("set-cookie: {} -> '{}'='{}'", raw_value, name, value);
-                Some((name, value))
-            })
-            .collect();
-        self.cookies.extend(new_cookies);
-    }
-}
-
-impl Drop for CookieStore {
-    fn drop(&mut self) {
-        // we need to ignore the error
-        let _ = self.save_to_session();
-    }
+fn session() -> Result<Arc<Session>> {
+    Session::get(SERVICE_NAME, ATCODER_TOP).context("failed to load session")
 }
 
 pub fn login(username: &str, password: &str) -> Result<()> {
-    let mut store = CookieStore::new();
+    let session = session()?;
+    session.clear();
 
     // access the login page and get csrf_token
-    let csrf_token = access_login_page(&mut store).context("fetching login page failed")?;
+    let csrf_token = access_login_page(&session).context("fetching login page failed")?;
 
     // post user authentication info
-    let success = post_account_info(&mut store, username, password, &csrf_token)
+    let success = post_account_info(&session, username, password, &csrf_token)
         .context("posting account info failed")?;
 
     ensure!(success, "invalid username or password");
+    session.save()
+}
 
-    Ok(())
+/// Saves the session cookie taken from the browser as the session.
+pub fn import_session(revel_session: &str) -> Result<()> {
+    let session = session()?;
+    session.clear();
+    session.insert_cookie(ATCODER_TOP, SESSION_COOKIE, revel_session)?;
+    session.save().context("failed to save the session")
 }
 
-fn new_client() -> Result<Client> {
-    ClientBuilder::new()
-        .redirect(Policy::none())
-        .build()
-        .context("failed to initialize the client")
+/// Finds the session cookie in the text exported from the browser: a
+/// `cookies.txt`, `REVEL_SESSION=...` (e.g. copied from the request header)
+/// or the bare value.
+pub fn parse_exported_session(text: &str) -> Option<String> {
+    let from_cookies_txt = text.lines().find_map(|line| {
+        let fields: Vec<_> = line.trim_end().split('\t').collect();
+        match *fields {
+            [domain, _, _, _, _, name, value]
+                if domain.contains("atcoder.jp") && name == SESSION_COOKIE =>
+            {
+                Some(value.to_string())
+            }
+            _ => None,
+        }
+    });
+
+    let from_header = || {
+        text.split(|ch: char| ch == ';' || ch.is_whitespace())
+            .find_map(|pair| pair.strip_prefix(SESSION_COOKIE)?.strip_prefix('='))
+            .map(ToString::to_string)
+    };
+
+    let bare = || {
+        let value = text.trim();
+        (!value.is_empty() && !value.contains(char::is_whitespace)).then(|| value.to_string())
+    };
+
+    from_cookies_txt
+        .or_else(from_header)
+        .or_else(bare)
+        .filter(|value| !value.is_empty())
 }
 
-fn access_login_page(store: &mut CookieStore) -> Result<String> {
+fn access_login_page(session: &Session) -> Result<String> {
     eprintln_debug!("fetching login page");
-    let req = new_client()?.get("https://atcoder.jp/login");
-    let res = store.with_cookie(req)?;
+    let res = session.client().get(LOGIN_URL).send()?;
     result_check(&res)?;
     let csrf_token = parse_csrf_token(res)?;
 
@@ -121,22 +89,24 @@ fn access_login_page(store: &mut CookieStore) -> Result<String> {
 
 /// Posts account information. Returns true if the login was successful
 fn post_account_info(
-    store: &mut CookieStore,
+    session: &Session,
     username: &str,
     password: &str,
     csrf_token: &str,
 ) -> Result<bool> {
-    let req = new_client()?
-        .post("https://atcoder.jp/login")
+    let res = session
+        .client()
+        .post(LOGIN_URL)
         .form(&hashmap! {
             "username" => username,
             "password" => password,
             "csrf_token" => csrf_token,
-        });
-    let res = store.with_cookie(req)?;
+        })
+        .send()?;
     result_check(&res)?;
 
-    Ok(store.cookies["REVEL_FLASH"].contains("success"))
+    // redirected back to the login page on failure
+    Ok(res.url().path() != "/login")
 }
 
 fn parse_csrf_token(res: Response) -> Result<String> {
@@ -164,16 +134,88 @@ fn result_check(res: &Response) -> Result<()> {
     }
 }
 
-pub fn authenticated_get(url: &str) -> Result<Response> {
-    let mut store = CookieStore::load_from_session().context("failed to load session")?;
-    let req = new_client()?.get(url);
-    let res = store.with_cookie(req).context("failed to request")?;
+/// Checks whether the saved session is still valid by visiting the home page.
+pub fn session_status() -> Result<SessionStatus> {
+    if !super::has_session_info(SERVICE_NAME) {
+        return Ok(SessionStatus::NotLoggedIn);
+    }
+
+    let res = authenticated_get("https://atcoder.jp/home", HeaderMap::new())?;
+    let text = res
+        .error_for_status()
+        .context("the server returned an error")?
+        .text()
+        .context("failed to get the text")?;
+
+    Ok(match parse_user_screen_name(&text) {
+        Some(username) => SessionStatus::LoggedIn { username },
+        None => SessionStatus::Expired,
+    })
+}
+
+/// Gets the name of the logged-in user embedded in the page, which is empty
+/// for guests.
+fn parse_user_screen_name(text: &str) -> Option<String> {
+    lazy_static! {
+        static ref RE_USER_SCREEN_NAME: Regex =
+            Regex::new(r#"var\s+userScreenName\s*=\s*"(?P<name>[^"]*)""#).unwrap();
+    }
+
+    RE_USER_SCREEN_NAME
+        .captures(text)
+        .map(|caps| caps["name"].to_string())
+        .filter(|name| !name.is_empty())
+}
+
+pub fn authenticated_get(url: &str, headers: HeaderMap) -> Result<Response> {
+    let session = session()?;
+    let res = session
+        .client()
+        .get(url)
+        .headers(headers)
+        .send()
+        .context("failed to request")?;
+    eprintln_debug!("response: {:?}", res);
 
-    if cfg!(debug_assertions) {
-        let req = new_client().unwrap().get(url);
-        let res = store.with_cookie(req).unwrap();
-        eprintln_debug!("response: {:?}", res.text().unwrap());
+    // the site may renew the session
+    if let Err(e) = session.save_if_changed() {
+        eprintln_warning!("failed to save the session: {:#}", e);
     }
 
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exported_session() {
+        let cookies_txt = "# Netscape HTTP Cookie File\n\
+            #HttpOnly_atcoder.jp\tFALSE\t/\tTRUE\t1700000000\tREVEL_SESSION\tabc%00def\n\
+            .example.com\tTRUE\t/\tFALSE\t0\tREVEL_SESSION\twrong\n";
+        assert_eq!(
+            parse_exported_session(cookies_txt).as_deref(),
+            Some("abc%00def")
+        );
+        assert_eq!(
+            parse_exported_session("language=ja; REVEL_SESSION=abc%00def; x=y").as_deref(),
+            Some("abc%00def")
+        );
+        assert_eq!(
+            parse_exported_session("abc%00def\n").as_deref(),
+            Some("abc%00def")
+        );
+        assert_eq!(parse_exported_session("\n"), None);
+    }
+
+    #[test]
+    fn test_parse_user_screen_name() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder/home.html"
+        ));
+        assert_eq!(parse_user_screen_name(text).as_deref(), Some("statiolake"));
+        assert_eq!(parse_user_screen_name(r#"var userScreenName = "";"#), None);
+    }
+}
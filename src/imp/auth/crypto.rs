@@ -0,0 +1,71 @@
+//! Encryption of the session files with a passphrase.
+
+use anyhow::{anyhow, ensure};
+use anyhow::{Context, Result};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/// Put at the head of the encrypted files.
+const MAGIC: &[u8] = b"procon-assistant encrypted v1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(MAGIC)
+}
+
+pub fn encrypt(passphrase: &str, plain: &[u8]) -> Result<Vec<u8>> {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = make_cipher(passphrase, &salt)?;
+    let encrypted = cipher
+        .encrypt(Nonce::from_slice(&nonce), plain)
+        .map_err(|_| anyhow!("failed to encrypt"))?;
+
+    Ok([MAGIC, &salt, &nonce, &encrypted].concat())
+}
+
+pub fn decrypt(passphrase: &str, contents: &[u8]) -> Result<Vec<u8>> {
+    let body = contents
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| anyhow!("not an encrypted file"))?;
+    ensure!(body.len() > SALT_LEN + NONCE_LEN, "the file is truncated");
+    let (salt, body) = body.split_at(SALT_LEN);
+    let (nonce, encrypted) = body.split_at(NONCE_LEN);
+
+    let cipher = make_cipher(passphrase, salt)?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), encrypted)
+        .map_err(|_| anyhow!("wrong passphrase or broken file"))
+}
+
+fn make_cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+    let mut key = [0; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("{}", e))
+        .context("failed to derive the key")?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let encrypted = encrypt("secret", b"REVEL_SESSION\tabc").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(
+            decrypt("secret", &encrypted).unwrap(),
+            b"REVEL_SESSION\tabc"
+        );
+        assert!(decrypt("wrong", &encrypted).is_err());
+    }
+}
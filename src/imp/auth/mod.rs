@@ -1,57 +1,114 @@
 use crate::eprintln_debug;
-use crate::imp::config;
-use std::fs::File;
+use crate::imp::config::{self, SessionStorage, CONFIG};
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
-use std::path::PathBuf;
-use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{env, fs, io};
 
 pub mod aoj;
 pub mod atcoder;
+mod crypto;
+
+/// The passphrase for the encrypted sessions is taken from this variable if
+/// set, or asked otherwise.
+const PASSPHRASE_ENV: &str = "PROCON_ASSISTANT_PASSPHRASE";
+
+lazy_static! {
+    // asked once in a process, as the session may be loaded many times
+    static ref PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// The state of the saved login session.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SessionStatus {
+    LoggedIn {
+        username: String,
+    },
+    /// The session is saved but no longer accepted by the site.
+    Expired,
+    NotLoggedIn,
+}
 
 pub fn ask_account_info(service_name: &str) -> (String, String) {
+    (ask_username(service_name), ask_password(service_name))
+}
+
+pub fn ask_username(service_name: &str) -> String {
     print!("  {} Username: ", service_name);
     io::stdout().flush().unwrap();
     let mut username = String::new();
     io::stdin().read_line(&mut username).unwrap();
 
+    username.trim().into()
+}
+
+pub fn ask_password(service_name: &str) -> String {
     print!("  {} Password: ", service_name);
     io::stdout().flush().unwrap();
     let password =
         rpassword::read_password().expect("critical error: failed to read your password input");
 
-    (username.trim().into(), password.trim().into())
+    password.trim().into()
 }
 
 pub fn place_to_store(service_name: &str) -> PathBuf {
     let mut path = config::config_dir();
     path.push("auth_info");
-    fs::create_dir_all(&path).expect("critical error: failed to create auth_info directory");
+    create_private_dir(&path).expect("critical error: failed to create auth_info directory");
     path.push(service_name);
 
     path
 }
 
-pub fn clear_session_info(service_name: &str) -> io::Result<()> {
+/// Removes the session from every storage.
+pub fn clear_session_info(service_name: &str) -> Result<()> {
     let place = place_to_store(service_name);
     if place.exists() {
-        fs::remove_file(&place)?;
+        fs::remove_file(&place)
+            .with_context(|| format!("failed to remove `{}`", place.display()))?;
+    }
+
+    #[cfg(feature = "keyring")]
+    match keyring_store::delete(service_name) {
+        Ok(()) => {}
+        Err(e) if CONFIG.auth.storage == SessionStorage::Keyring => return Err(e),
+        // the keyring may not be available at all where it is not used
+        Err(e) => eprintln_debug!("skipping the keyring: {:#}", e),
     }
+
     Ok(())
 }
 
-pub fn store_session_info(service_name: &str, contents: &[u8]) -> io::Result<()> {
-    clear_session_info(service_name).expect("critical error: failed to clear session info");
+pub fn store_session_info(service_name: &str, contents: &[u8]) -> Result<()> {
+    clear_session_info(service_name).context("failed to clear session info")?;
 
     let place = place_to_store(service_name);
-    if let Some(parent) = place.parent() {
-        fs::create_dir_all(parent)?;
+    match CONFIG.auth.storage {
+        SessionStorage::File => write_private(&place, contents),
+        SessionStorage::Encrypted => {
+            let encrypted = crypto::encrypt(&passphrase()?, contents)?;
+            write_private(&place, &encrypted)
+        }
+        SessionStorage::Keyring => keyring_store::store(service_name, contents),
     }
-    File::create(place)?.write_all(contents)?;
+    .with_context(|| format!("failed to store the session for {}", service_name))
+}
 
-    Ok(())
+pub fn has_session_info(service_name: &str) -> bool {
+    match CONFIG.auth.storage {
+        SessionStorage::Keyring => keyring_store::load(service_name).is_ok(),
+        _ => place_to_store(service_name).exists(),
+    }
 }
 
-pub fn load_session_info(service_name: &str) -> io::Result<Vec<u8>> {
+pub fn load_session_info(service_name: &str) -> Result<Vec<u8>> {
+    if CONFIG.auth.storage == SessionStorage::Keyring {
+        return keyring_store::load(service_name);
+    }
+
     let place = place_to_store(service_name);
     let mut contents = Vec::new();
     eprintln_debug!(
@@ -59,7 +116,127 @@ pub fn load_session_info(service_name: &str) -> io::Result<Vec<u8>> {
         service_name,
         place.display()
     );
-    File::open(place)?.read_to_end(&mut contents)?;
+    File::open(&place)
+        .and_then(|mut f| f.read_to_end(&mut contents))
+        .with_context(|| format!("failed to read `{}`", place.display()))?;
+    restrict_permissions(&place)?;
+
+    if crypto::is_encrypted(&contents) {
+        crypto::decrypt(&passphrase()?, &contents).context("failed to decrypt the session")
+    } else {
+        Ok(contents)
+    }
+}
+
+fn passphrase() -> Result<String> {
+    let mut cached = PASSPHRASE.lock().unwrap();
+    if let Some(passphrase) = &*cached {
+        return Ok(passphrase.clone());
+    }
+
+    let passphrase = match env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => rpassword::prompt_password("  Passphrase for the sessions: ")
+            .context("failed to read the passphrase")?,
+    };
+    *cached = Some(passphrase.clone());
+
+    Ok(passphrase)
+}
+
+/// Creates the directory readable only by the user.
+pub fn create_private_dir(path: &Path) -> io::Result<()> {
+    fs::create_dir_all(path)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))?;
+    }
+
+    Ok(())
+}
+
+/// Writes the file readable only by the user.
+pub fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options
+        .open(path)
+        .and_then(|mut f| f.write_all(contents))
+        .with_context(|| format!("failed to write `{}`", path.display()))?;
+
+    restrict_permissions(path)
+}
+
+/// Fixes the permissions of the session file written by older versions.
+fn restrict_permissions(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)
+            .with_context(|| format!("failed to get metadata of `{}`", path.display()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("failed to restrict `{}`", path.display()))?;
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+#[cfg(feature = "keyring")]
+mod keyring_store {
+    use anyhow::{Context, Result};
+    use keyring::{Entry, Error};
 
-    Ok(contents)
+    const SERVICE: &str = "procon-assistant";
+
+    fn entry(service_name: &str) -> Result<Entry> {
+        Entry::new(SERVICE, service_name).context("failed to open the keyring")
+    }
+
+    pub fn store(service_name: &str, contents: &[u8]) -> Result<()> {
+        entry(service_name)?
+            .set_secret(contents)
+            .context("failed to store to the keyring")
+    }
+
+    pub fn load(service_name: &str) -> Result<Vec<u8>> {
+        entry(service_name)?
+            .get_secret()
+            .context("failed to load from the keyring")
+    }
+
+    pub fn delete(service_name: &str) -> Result<()> {
+        match entry(service_name)?.delete_credential() {
+            Ok(()) | Err(Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("failed to delete from the keyring"),
+        }
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+mod keyring_store {
+    use anyhow::{bail, Result};
+
+    pub fn store(_service_name: &str, _contents: &[u8]) -> Result<()> {
+        bail!("keyring is not supported in this build; rebuild with `--features keyring`");
+    }
+
+    pub fn load(_service_name: &str) -> Result<Vec<u8>> {
+        bail!("keyring is not supported in this build; rebuild with `--features keyring`");
+    }
 }
@@ -0,0 +1,147 @@
+use crate::imp::fs::DATA_DIR;
+use anyhow::{Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CASE_INFO_FILE: &str = "cases.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    #[serde(rename = "fetched")]
+    Fetched,
+    #[serde(rename = "manual")]
+    Manual,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, b: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Origin::Fetched => write!(b, "fetched"),
+            Origin::Manual => write!(b, "manual"),
+        }
+    }
+}
+
+/// Information about a test case which is not in the test case files.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CaseInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Origin>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<String>,
+}
+
+/// Information about all test cases in the current directory, keyed by the
+/// test case index.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(transparent)]
+pub struct CaseInfos {
+    infos: BTreeMap<i32, CaseInfo>,
+}
+
+impl CaseInfos {
+    pub fn load() -> Result<CaseInfos> {
+        CaseInfos::load_from(&default_path())
+    }
+
+    pub fn load_from(path: &Path) -> Result<CaseInfos> {
+        if !path.exists() {
+            return Ok(CaseInfos::default());
+        }
+
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("failed to parse `{}`", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&default_path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create `{}`", parent.display()))?;
+        }
+
+        let text = serde_json::to_string_pretty(self).context("failed to serialize")?;
+        fs::write(path, text).with_context(|| format!("failed to write `{}`", path.display()))
+    }
+
+    pub fn get(&self, idx: i32) -> Option<&CaseInfo> {
+        self.infos.get(&idx)
+    }
+
+    pub fn set_origin(&mut self, idx: i32, origin: Origin) {
+        // a new test case is created; the previous verdict is meaningless.
+        self.infos.insert(
+            idx,
+            CaseInfo {
+                origin: Some(origin),
+                verdict: None,
+            },
+        );
+    }
+
+    pub fn set_verdict(&mut self, idx: i32, verdict: String) {
+        self.infos.entry(idx).or_default().verdict = Some(verdict);
+    }
+
+    /// Removes the information of the specified test case and returns it.
+    pub fn take(&mut self, idx: i32) -> Option<CaseInfo> {
+        self.infos.remove(&idx)
+    }
+
+    pub fn insert(&mut self, idx: i32, info: CaseInfo) {
+        self.infos.insert(idx, info);
+    }
+
+    /// Follows renumbering of test cases. See `test_case::renumber_test_cases`.
+    pub fn renumber(&mut self, moves: &[(i32, i32)]) {
+        let moved = moves
+            .iter()
+            .filter_map(|&(from, to)| Some((to, self.infos.remove(&from)?)))
+            .collect::<Vec<_>>();
+        for &(_, to) in moves {
+            self.infos.remove(&to);
+        }
+        self.infos.extend(moved);
+    }
+}
+
+/// Records the origin of newly created test cases.
+pub fn record_origin(indices: &[i32], origin: Origin) -> Result<()> {
+    let mut infos = CaseInfos::load()?;
+    for &idx in indices {
+        infos.set_origin(idx, origin);
+    }
+
+    infos.save()
+}
+
+fn default_path() -> PathBuf {
+    Path::new(DATA_DIR).join(CASE_INFO_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renumber() {
+        let mut infos = CaseInfos::default();
+        infos.set_origin(1, Origin::Fetched);
+        infos.set_origin(2, Origin::Manual);
+        infos.set_verdict(3, "WA".to_string());
+
+        // swap 1 and 2, and move 3 to 4
+        infos.renumber(&[(1, 2), (2, 1), (3, 4)]);
+        assert_eq!(infos.get(1).unwrap().origin, Some(Origin::Manual));
+        assert_eq!(infos.get(2).unwrap().origin, Some(Origin::Fetched));
+        assert!(infos.get(3).is_none());
+        assert_eq!(infos.get(4).unwrap().verdict.as_deref(), Some("WA"));
+    }
+}
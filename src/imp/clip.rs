@@ -1,3 +1,5 @@
+use anyhow::Result;
+
 #[cfg(not(unix))]
 pub fn set_clipboard(content: String) {
     use clipboard::{ClipboardContext, ClipboardProvider};
@@ -32,3 +34,36 @@ pub fn set_clipboard(content: String) {
     // clipboard selection --- paste with Ctrl + V
     run_xclip(content.as_bytes(), "c");
 }
+
+#[cfg(not(unix))]
+pub fn get_clipboard() -> Result<String> {
+    use anyhow::anyhow;
+    use clipboard::{ClipboardContext, ClipboardProvider};
+    let mut provider: ClipboardContext =
+        ClipboardProvider::new().map_err(|e| anyhow!("cannot get clipboard provider: {}", e))?;
+    provider
+        .get_contents()
+        .map_err(|e| anyhow!("cannot get contents of clipboard: {}", e))
+}
+
+#[cfg(unix)]
+pub fn get_clipboard() -> Result<String> {
+    use anyhow::{ensure, Context};
+    use std::process::{Command, Stdio};
+
+    // read the clipboard selection --- the one pasted with Ctrl + V
+    let output = Command::new("xclip")
+        .arg("-o")
+        .arg("-sel")
+        .arg("c")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .context("failed to run xclip")?;
+    ensure!(
+        output.status.success(),
+        "xclip exited unsuccessfully; is the clipboard empty?"
+    );
+
+    String::from_utf8(output.stdout).context("the clipboard contents is not valid UTF-8")
+}
@@ -3,6 +3,7 @@ use lazy_static::lazy_static;
 use serde::de::value;
 use serde::de::{Deserialize, IntoDeserializer};
 use serde_derive::Deserialize;
+use std::collections::HashMap;
 use std::fs::File;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -29,11 +30,19 @@ pub struct ConfigFile {
     #[serde(default)]
     pub run: Run,
     #[serde(default)]
+    pub fetch: Fetch,
+    #[serde(default)]
+    pub download: Download,
+    #[serde(default)]
     pub clip: Clip,
     #[serde(default)]
     pub langs: Langs,
     #[serde(default)]
     pub doc: Doc,
+    #[serde(default)]
+    pub auth: Auth,
+    #[serde(default)]
+    pub http: Http,
 }
 
 #[derive(Deserialize)]
@@ -74,6 +83,8 @@ pub struct Addcase {
     pub editor_command: Vec<String>,
     #[serde(default = "Addcase::default_wait_for_editor_finish")]
     pub wait_for_editor_finish: bool,
+    #[serde(default = "Addcase::default_separator")]
+    pub separator: String,
 }
 
 #[derive(Deserialize)]
@@ -84,6 +95,28 @@ pub struct Run {
     pub eps_for_float: f64,
 }
 
+#[derive(Deserialize, Default)]
+pub struct Fetch {
+    /// Selectors used by the generic provider, keyed by the domain.
+    #[serde(default)]
+    pub sites: HashMap<String, SiteSelectors>,
+}
+
+#[derive(Deserialize)]
+pub struct Download {
+    /// How long `download --wait` keeps polling after the contest starts.
+    #[serde(default = "Download::default_wait_grace_minutes")]
+    pub wait_grace_minutes: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct SiteSelectors {
+    pub input: String,
+    pub output: String,
+    #[serde(default)]
+    pub statement: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct Clip {
     #[serde(default = "Clip::default_minify")]
@@ -153,6 +186,40 @@ pub struct Doc {
     pub browser: Option<Vec<String>>,
 }
 
+#[derive(Deserialize)]
+pub struct Http {
+    #[serde(default = "Http::default_user_agent")]
+    pub user_agent: String,
+    #[serde(default = "Http::default_timeout_seconds")]
+    pub timeout_seconds: u64,
+    #[serde(default = "Http::default_connect_timeout_seconds")]
+    pub connect_timeout_seconds: u64,
+    /// The proxy for all requests, such as `http://proxy.example.com:8080`.
+    #[serde(default)]
+    pub proxy: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct Auth {
+    /// Where the login sessions are stored.
+    #[serde(default)]
+    pub storage: SessionStorage,
+}
+
+#[derive(Deserialize, Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SessionStorage {
+    /// A file readable only by the user
+    #[default]
+    #[serde(rename = "file")]
+    File,
+    /// A file encrypted with a passphrase
+    #[serde(rename = "encrypted")]
+    Encrypted,
+    /// The keyring of the OS (Secret Service on Linux)
+    #[serde(rename = "keyring")]
+    Keyring,
+}
+
 impl ConfigFile {
     pub fn get_config() -> Result<ConfigFile> {
         let config_path = config_dir().join("config.json");
@@ -241,6 +308,7 @@ impl Default for Addcase {
             give_argument_once: Addcase::default_give_argument_once(),
             editor_command: Addcase::default_editor_command(),
             wait_for_editor_finish: Addcase::default_wait_for_editor_finish(),
+            separator: Addcase::default_separator(),
         }
     }
 }
@@ -257,6 +325,10 @@ impl Addcase {
     pub fn default_wait_for_editor_finish() -> bool {
         General::default_wait_for_editor_finish()
     }
+
+    pub fn default_separator() -> String {
+        "---".to_string()
+    }
 }
 
 impl Default for Run {
@@ -278,6 +350,20 @@ impl Run {
     }
 }
 
+impl Default for Download {
+    fn default() -> Self {
+        Download {
+            wait_grace_minutes: Download::default_wait_grace_minutes(),
+        }
+    }
+}
+
+impl Download {
+    pub fn default_wait_grace_minutes() -> u64 {
+        30
+    }
+}
+
 impl Default for Clip {
     fn default() -> Self {
         Clip {
@@ -292,6 +378,31 @@ impl Clip {
     }
 }
 
+impl Default for Http {
+    fn default() -> Self {
+        Http {
+            user_agent: Http::default_user_agent(),
+            timeout_seconds: Http::default_timeout_seconds(),
+            connect_timeout_seconds: Http::default_connect_timeout_seconds(),
+            proxy: None,
+        }
+    }
+}
+
+impl Http {
+    pub fn default_user_agent() -> String {
+        concat!("procon-assistant/", env!("CARGO_PKG_VERSION")).to_string()
+    }
+
+    pub fn default_timeout_seconds() -> u64 {
+        30
+    }
+
+    pub fn default_connect_timeout_seconds() -> u64 {
+        10
+    }
+}
+
 impl Default for Cpp {
     fn default() -> Self {
         Cpp {
@@ -0,0 +1,98 @@
+use super::{ContestProvider, Fetcher, Fetchers};
+use crate::imp::fetch::aoj as fetch;
+use crate::imp::fetch::aoj::AOJ_API_TOP;
+use anyhow::ensure;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use serde_derive::Deserialize;
+
+pub struct Aoj {
+    contest_id: String,
+    url: String,
+}
+
+impl Aoj {
+    /// Creates a provider for a course (like `ITP1`) or a volume (like `0`).
+    pub fn new(contest_id: String) -> Result<Aoj> {
+        ensure!(
+            !contest_id.is_empty() && contest_id.chars().all(|ch| ch.is_ascii_alphanumeric()),
+            "invalid format for contest id: `{}`",
+            contest_id
+        );
+
+        let url = if contest_id.chars().all(|ch| ch.is_ascii_digit()) {
+            format!("{}/problems/volumes/{}", AOJ_API_TOP, contest_id)
+        } else {
+            format!("{}/problems/courses/{}", AOJ_API_TOP, contest_id)
+        };
+
+        Ok(Aoj { contest_id, url })
+    }
+}
+
+impl ContestProvider for Aoj {
+    fn site_name(&self) -> &str {
+        "Aizu Online Judge"
+    }
+
+    fn contest_id(&self) -> &str {
+        &self.contest_id
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn make_fetchers(&self) -> Result<Fetchers> {
+        let text = fetch::download_text(&self.url).context("failed to get the problem list")?;
+        let fetchers = parse_problems(&text)?
+            .into_iter()
+            .map(|problem_id| {
+                Ok(Fetcher {
+                    provider: Some(Box::new(fetch::Aoj::new(problem_id.clone())?)),
+                    problem_name: problem_id,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Fetchers {
+            fetchers,
+            contest_id: self.contest_id.clone(),
+            unique_contest_id: true,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ProblemList {
+    problems: Vec<ProblemEntry>,
+}
+
+#[derive(Deserialize)]
+struct ProblemEntry {
+    id: String,
+}
+
+/// Gets the problem ids from the problem list of a course or a volume.
+fn parse_problems(text: &str) -> Result<Vec<String>> {
+    let list: ProblemList =
+        serde_json::from_str(text).context("failed to parse the problem list")?;
+    Ok(list.problems.into_iter().map(|p| p.id).collect_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_problems() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/aoj/course.json"
+        ));
+        assert_eq!(
+            parse_problems(text).unwrap(),
+            ["ITP1_1_A", "ITP1_1_B", "ITP1_1_C"]
+        );
+    }
+}
@@ -1,15 +1,18 @@
 use super::{ContestProvider, Fetchers};
 use crate::imp::auth::atcoder as auth;
+use crate::imp::auth::SessionStatus;
 use crate::imp::fetch::atcoder as fetch;
 use crate::imp::fetch::atcoder::ATCODER_TOP;
 use anyhow::ensure;
 use anyhow::{Context, Result};
-use easy_scraper::Pattern;
+use chrono::{DateTime, FixedOffset};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
+use scraper::{Html, Selector};
 
 lazy_static! {
+    static ref RE_CONTEST_ID: Regex = Regex::new(r"^[A-Za-z0-9_-]+$").unwrap();
     static ref RE_DIRECT_URL_CONTEST_NAME: Regex =
         Regex::new(r"contests/(?P<name>[^/]*)/tasks").unwrap();
 }
@@ -32,11 +35,11 @@ pub enum Contest {
 impl Contest {
     pub fn from_contest_id(contest_id: String) -> Result<Contest> {
         ensure!(
-            contest_id.len() == 6,
+            RE_CONTEST_ID.is_match(&contest_id),
             "invalid format for contest id: `{}`",
             contest_id
         );
-        let url = format!("{}/contests/{}/tasks", ATCODER_TOP, contest_id);
+        let url = fetch::tasks_url(&contest_id);
 
         Ok(Contest::ContestId { contest_id, url })
     }
@@ -77,8 +80,8 @@ impl ContestProvider for AtCoder {
     }
 
     fn make_fetchers(&self) -> Result<Fetchers> {
-        let text = download_text(self.url())?;
-        let fetchers = parse_table(&text)
+        let text = fetch::download_text(self.url())?;
+        let fetchers = fetch::parse_task_list(&text)
             .into_iter()
             .map(|mut row| {
                 let problem = fetch::Problem::from_url(format!("{}{}", ATCODER_TOP, row.url));
@@ -86,7 +89,7 @@ impl ContestProvider for AtCoder {
 
                 row.problem.make_ascii_lowercase();
                 super::Fetcher {
-                    provider: fetcher as _,
+                    provider: Some(fetcher as _),
                     problem_name: row.problem,
                 }
             })
@@ -98,45 +101,48 @@ impl ContestProvider for AtCoder {
             unique_contest_id: true,
         })
     }
-}
 
-fn download_text(url: &str) -> Result<String> {
-    auth::authenticated_get(url)
-        .with_context(|| format!("failed to fetch `{}` with logged in", url))?
-        .text()
-        .context("failed to get the html")
+    fn start_time(&self) -> Result<Option<DateTime<FixedOffset>>> {
+        let contest_id = self.contest_id();
+        if contest_id == "Unknown" {
+            return Ok(None);
+        }
+
+        let url = format!("{}/contests/{}", ATCODER_TOP, contest_id);
+        let text = fetch::download_text(&url).context("failed to get the contest page")?;
+        parse_start_time(&text)
+    }
+
+    fn session_status(&self) -> Result<Option<SessionStatus>> {
+        auth::session_status().map(Some)
+    }
 }
 
-struct TableRow {
-    problem: String,
-    url: String,
+/// Gets the start time from the contest duration in the contest page.
+fn parse_start_time(text: &str) -> Result<Option<DateTime<FixedOffset>>> {
+    let document = Html::parse_document(text);
+    let sel_time = Selector::parse(".contest-duration time").unwrap();
+    let time = match document.select(&sel_time).next() {
+        Some(time) => time.text().collect::<String>(),
+        None => return Ok(None),
+    };
+
+    DateTime::parse_from_str(time.trim(), "%Y-%m-%d %H:%M:%S%z")
+        .map(Some)
+        .with_context(|| format!("failed to parse the start time `{}`", time))
 }
 
-fn parse_table(text: &str) -> Vec<TableRow> {
-    let pat = Pattern::new(
-        r#"<h2>Tasks</h2>
-...
-<div>
-<table><tbody>
-<tr>
-<td class="text-center no-break">
-<a href="{{url}}">{{problem}}</a>
-</td>
-<td>
-<a href="{{url}}"
->{{name}}</a
->
-</td>
-</tr>
-</tbody></table></div>"#,
-    )
-    .unwrap();
-
-    pat.matches(text)
-        .into_iter()
-        .map(|r| TableRow {
-            problem: r["problem"].clone(),
-            url: r["url"].clone(),
-        })
-        .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start_time() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder/contest.html"
+        ));
+        let start_time = parse_start_time(text).unwrap().unwrap();
+        assert_eq!(start_time.to_rfc3339(), "2023-04-29T21:00:00+09:00");
+    }
 }
@@ -0,0 +1,134 @@
+use super::{ContestProvider, Fetcher, Fetchers};
+use crate::imp::fetch::codeforces as fetch;
+use crate::imp::fetch::codeforces::CODEFORCES_TOP;
+use anyhow::ensure;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{Html, Selector};
+
+lazy_static! {
+    static ref RE_DIRECT_URL_CONTEST_ID: Regex = Regex::new(r"contest/(?P<id>\d+)").unwrap();
+}
+
+pub struct Codeforces {
+    contest: Contest,
+}
+
+impl Codeforces {
+    pub fn new(contest: Contest) -> Codeforces {
+        Codeforces { contest }
+    }
+}
+
+pub enum Contest {
+    ContestId { contest_id: String, url: String },
+    DirectUrl { url: String },
+}
+
+impl Contest {
+    pub fn from_contest_id(contest_id: String) -> Result<Contest> {
+        ensure!(
+            !contest_id.is_empty() && contest_id.chars().all(|ch| ch.is_ascii_digit()),
+            "invalid format for contest id: `{}`",
+            contest_id
+        );
+        let url = format!("{}/contest/{}", CODEFORCES_TOP, contest_id);
+
+        Ok(Contest::ContestId { contest_id, url })
+    }
+
+    pub fn from_url(url: String) -> Contest {
+        Contest::DirectUrl { url }
+    }
+
+    pub fn contest_id(&self) -> &str {
+        match self {
+            Contest::ContestId { contest_id, .. } => contest_id,
+            Contest::DirectUrl { url } => match RE_DIRECT_URL_CONTEST_ID.captures(url) {
+                None => "Unknown",
+                Some(caps) => caps.name("id").unwrap().as_str(),
+            },
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            Contest::ContestId { url, .. } => url,
+            Contest::DirectUrl { url } => url,
+        }
+    }
+}
+
+impl ContestProvider for Codeforces {
+    fn site_name(&self) -> &str {
+        "Codeforces"
+    }
+
+    fn contest_id(&self) -> &str {
+        self.contest.contest_id()
+    }
+
+    fn url(&self) -> &str {
+        self.contest.url()
+    }
+
+    fn make_fetchers(&self) -> Result<Fetchers> {
+        let text = fetch::download_text(self.url()).context("failed to get the contest page")?;
+        let fetchers = parse_table(&text)
+            .into_iter()
+            .map(|row| {
+                let problem = fetch::Problem::from_url(format!("{}{}", CODEFORCES_TOP, row.url));
+                Fetcher {
+                    provider: Some(Box::new(fetch::Codeforces::new(problem))),
+                    problem_name: row.problem.to_ascii_lowercase(),
+                }
+            })
+            .collect_vec();
+
+        Ok(Fetchers {
+            fetchers,
+            contest_id: self.contest_id().to_string(),
+            unique_contest_id: true,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct TableRow {
+    problem: String,
+    url: String,
+}
+
+fn parse_table(text: &str) -> Vec<TableRow> {
+    let document = Html::parse_document(text);
+    let sel_problem = Selector::parse("table.problems td.id a").unwrap();
+
+    document
+        .select(&sel_problem)
+        .filter_map(|a| {
+            Some(TableRow {
+                problem: a.text().collect::<String>().trim().to_string(),
+                url: a.value().attr("href")?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/codeforces/contest.html"
+        ));
+        let rows = parse_table(text);
+        let problems = rows.iter().map(|r| r.problem.as_str()).collect_vec();
+        assert_eq!(problems, ["A", "B1", "B2"]);
+        assert_eq!(rows[1].url, "/contest/1234/problem/B1");
+    }
+}
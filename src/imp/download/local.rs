@@ -1,8 +1,8 @@
 use super::Fetchers;
 use crate::imp::fetch::ProblemDescriptor;
-use anyhow::ensure;
+use anyhow::{bail, ensure};
 use anyhow::{Context, Result};
-use itertools::Itertools;
+use std::collections::HashMap;
 use std::path::Path;
 use std::{fs, str};
 
@@ -30,38 +30,42 @@ impl super::ContestProvider for Local {
     }
 
     fn make_fetchers(&self) -> Result<Fetchers> {
-        let problem_list = load_problem_list(self.file_path.clone())?;
+        let problem_list = load_problem_list(&self.file_path)?;
         make_fetcher(problem_list)
     }
 }
 
-fn make_fetcher(problem_list: Vec<String>) -> Result<Fetchers> {
-    ensure!(
-        problem_list.len() <= 26,
-        "too many problems; max is 26 (a - z) but listed {} problems",
-        problem_list.len()
-    );
-    let dscs = problem_list
-        .into_iter()
-        .map(|dsc| ProblemDescriptor::parse(&dsc).context("failed to parse a problem"))
-        .map(|dsc| dsc.and_then(|dsc| dsc.resolve_provider().context("failed to get the provider")))
-        .collect::<Result<Vec<_>>>()?;
+/// A line of the problem list.
+#[derive(Debug, PartialEq, Eq)]
+struct Entry {
+    line: usize,
+    problem_name: String,
+    /// `None` for a problem only created locally.
+    descriptor: Option<String>,
+}
 
-    let fetchers = dscs
+fn make_fetcher(problem_list: Vec<Entry>) -> Result<Fetchers> {
+    let fetchers = problem_list
         .into_iter()
-        .enumerate()
-        .map(|(idx, fetcher)| {
-            let problem = b'a'
-                .checked_add(idx as u8)
-                .expect("internal error: this must not overflow as it's checked before.");
-            let problem = (problem as char).to_string();
-
-            super::Fetcher {
-                provider: fetcher,
-                problem_name: problem,
-            }
+        .map(|entry| {
+            let provider = entry
+                .descriptor
+                .map(|dsc| {
+                    ProblemDescriptor::parse(&dsc)
+                        .context("failed to parse a problem")
+                        .and_then(|dsc| {
+                            dsc.resolve_provider().context("failed to get the provider")
+                        })
+                        .with_context(|| format!("line {}: invalid problem `{}`", entry.line, dsc))
+                })
+                .transpose()?;
+
+            Ok(super::Fetcher {
+                provider,
+                problem_name: entry.problem_name,
+            })
         })
-        .collect_vec();
+        .collect::<Result<Vec<_>>>()?;
 
     Ok(Fetchers {
         fetchers,
@@ -70,18 +74,125 @@ fn make_fetcher(problem_list: Vec<String>) -> Result<Fetchers> {
     })
 }
 
-fn load_problem_list(file_path: String) -> Result<Vec<String>> {
-    let problems_path = Path::new(&file_path);
+fn load_problem_list(file_path: &str) -> Result<Vec<Entry>> {
+    let problems_path = Path::new(file_path);
     ensure!(problems_path.exists(), "problem list is not specified");
     let content = fs::read_to_string(problems_path)
         .with_context(|| format!("failed to open problems: `{}`", problems_path.display()))?;
-    let res = content
-        .split('\n')
-        .map(str::trim)
-        .filter(|&x| !x.starts_with('#'))
-        .filter(|&x| !x.is_empty())
-        .map(Into::into)
-        .collect_vec();
-
-    Ok(res)
+
+    parse_problem_list(&content)
+        .with_context(|| format!("failed to parse problems: `{}`", problems_path.display()))
+}
+
+/// Parses the problem list. Each line is one of the followings:
+///
+/// - `{problem-descriptor}`: named by its position (`a`, `b`, ..., `z`, `aa`, ...)
+/// - `{problem-name} {problem-descriptor}`
+/// - `{problem-name}`: a problem with no remote source
+fn parse_problem_list(content: &str) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let mut name_lines = HashMap::new();
+
+    let lines = content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|&(_, line)| !line.starts_with('#') && !line.is_empty());
+    for (pos, (line, text)) in lines.enumerate() {
+        let tokens: Vec<_> = text.split_whitespace().collect();
+        let (problem_name, descriptor) = match *tokens {
+            [dsc] if dsc.contains(':') => (positional_name(pos), Some(dsc)),
+            [name] => (name.to_string(), None),
+            [name, dsc] => (name.to_string(), Some(dsc)),
+            _ => bail!("line {}: too many fields: `{}`", line, text),
+        };
+
+        ensure!(
+            !problem_name.contains(':') && is_valid_dirname(&problem_name),
+            "line {}: invalid problem name `{}`",
+            line,
+            problem_name
+        );
+        if let Some(first) = name_lines.insert(problem_name.clone(), line) {
+            bail!(
+                "line {}: duplicate problem name `{}` (first used on line {})",
+                line,
+                problem_name,
+                first
+            );
+        }
+
+        entries.push(Entry {
+            line,
+            problem_name,
+            descriptor: descriptor.map(ToString::to_string),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn is_valid_dirname(name: &str) -> bool {
+    name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+/// `a`, `b`, ..., `z`, `aa`, `ab`, ...
+pub(super) fn positional_name(pos: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = pos + 1;
+    while n > 0 {
+        n -= 1;
+        name.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).expect("internal error: name must be ascii")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_problem_list() {
+        let content = "# practice\natcoder:abc001_a\n\ndp_a atcoder:dp_a\nmine\n";
+        let entries = parse_problem_list(content).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    line: 2,
+                    problem_name: "a".to_string(),
+                    descriptor: Some("atcoder:abc001_a".to_string()),
+                },
+                Entry {
+                    line: 4,
+                    problem_name: "dp_a".to_string(),
+                    descriptor: Some("atcoder:dp_a".to_string()),
+                },
+                Entry {
+                    line: 5,
+                    problem_name: "mine".to_string(),
+                    descriptor: None,
+                },
+            ]
+        );
+
+        let err = parse_problem_list("atcoder:abc001_a\natcoder:abc001_b\na aoj:0000\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "line 3: duplicate problem name `a` (first used on line 1)"
+        );
+    }
+
+    #[test]
+    fn test_positional_name() {
+        assert_eq!(positional_name(0), "a");
+        assert_eq!(positional_name(25), "z");
+        assert_eq!(positional_name(26), "aa");
+        assert_eq!(positional_name(27), "ab");
+        assert_eq!(positional_name(26 + 26 * 26), "aaa");
+    }
 }
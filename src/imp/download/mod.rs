@@ -1,12 +1,22 @@
+pub mod aoj;
 pub mod atcoder;
+pub mod codeforces;
 pub mod local;
+pub mod virtual_contest;
+pub mod yukicoder;
 
+use self::aoj::Aoj;
 use self::atcoder::{AtCoder, Contest as AtCoderContest};
+use self::codeforces::{Codeforces, Contest as CodeforcesContest};
 use self::local::Local;
+use self::virtual_contest::{Source as VirtualContestSource, VirtualContest};
+use self::yukicoder::{Contest as YukicoderContest, Yukicoder};
+use crate::imp::auth::SessionStatus;
 use crate::imp::fetch::TestCaseProvider;
 use crate::imp::fs;
 use anyhow::{bail, ensure};
 use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
 use itertools::Itertools;
 use std::path::PathBuf;
 use std::{env, fmt};
@@ -16,6 +26,16 @@ pub trait ContestProvider {
     fn contest_id(&self) -> &str;
     fn url(&self) -> &str;
     fn make_fetchers(&self) -> Result<Fetchers>;
+
+    /// The start time of the contest, if the site tells it.
+    fn start_time(&self) -> Result<Option<DateTime<FixedOffset>>> {
+        Ok(None)
+    }
+
+    /// The login session used to see the problems, if the site has one.
+    fn session_status(&self) -> Result<Option<SessionStatus>> {
+        Ok(None)
+    }
 }
 
 pub struct Fetchers {
@@ -25,7 +45,9 @@ pub struct Fetchers {
 }
 
 pub struct Fetcher {
-    pub provider: Box<dyn TestCaseProvider>,
+    /// `None` for a problem which has no remote source; only its directory
+    /// is created.
+    pub provider: Option<Box<dyn TestCaseProvider>>,
     pub problem_name: String,
 }
 
@@ -33,7 +55,7 @@ impl fmt::Debug for Fetcher {
     fn fmt(&self, b: &mut fmt::Formatter) -> fmt::Result {
         b.debug_struct("Fetcher")
             .field("problem", &self.problem_name)
-            .field("fetcher", &self.provider.url())
+            .field("fetcher", &self.provider.as_ref().map(|p| p.url()))
             .finish()
     }
 }
@@ -101,6 +123,9 @@ impl ContestDescriptor {
 
     pub fn resolve_provider(self) -> Result<Box<dyn ContestProvider>> {
         match &*self.contest_site {
+            "aoj" => Aoj::new(self.contest_id)
+                .context("failed to parse contest-id")
+                .map(|p| Box::new(p) as _),
             "atcoder" | "at" => {
                 let contest = if self.contest_id.starts_with("http") {
                     AtCoderContest::from_url(self.contest_id.to_string())
@@ -111,6 +136,29 @@ impl ContestDescriptor {
                 let provider = AtCoder::new(contest);
                 Ok(Box::new(provider) as _)
             }
+            "codeforces" | "cf" => {
+                let contest = if self.contest_id.starts_with("http") {
+                    CodeforcesContest::from_url(self.contest_id)
+                } else {
+                    CodeforcesContest::from_contest_id(self.contest_id)
+                        .context("failed to parse contest-id")?
+                };
+                Ok(Box::new(Codeforces::new(contest)) as _)
+            }
+            "yukicoder" | "yuki" => {
+                let contest = if self.contest_id.starts_with("http") {
+                    YukicoderContest::from_url(self.contest_id)
+                } else {
+                    YukicoderContest::from_contest_id(self.contest_id)
+                        .context("failed to parse contest-id")?
+                };
+                Ok(Box::new(Yukicoder::new(contest)) as _)
+            }
+            "virtual" | "vc" => {
+                let source = VirtualContestSource::parse(self.contest_id)
+                    .context("failed to parse contest-id")?;
+                Ok(Box::new(VirtualContest::new(source)) as _)
+            }
             "local" => {
                 let provider = Local::from_path(self.contest_id);
                 Ok(Box::new(provider) as _)
@@ -0,0 +1,389 @@
+use super::{ContestProvider, Fetcher, Fetchers};
+use crate::eprintln_warning;
+use crate::imp::auth::atcoder as auth;
+use crate::imp::auth::SessionStatus;
+use crate::imp::fetch::atcoder as fetch;
+use crate::imp::fetch::{FetchedProblem, TestCaseProvider};
+use crate::imp::http_cache;
+use anyhow::{anyhow, ensure};
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+const ATCODER_PROBLEMS_API: &str = "https://kenkoooo.com/atcoder/internal-api/contest/get";
+const ATCODER_PROBLEMS_LIST: &str = "https://kenkoooo.com/atcoder/resources/problems.json";
+
+lazy_static! {
+    static ref RE_ATCODER_PROBLEMS_URL: Regex =
+        Regex::new(r"kenkoooo\.com/atcoder/#/contest/show/(?P<id>[0-9a-f-]+)").unwrap();
+}
+
+/// A virtual contest of AtCoder problems, read from the export of AtCoder
+/// Problems (JSON) or a list of task URLs.
+pub struct VirtualContest {
+    source: Source,
+}
+
+impl VirtualContest {
+    pub fn new(source: Source) -> VirtualContest {
+        VirtualContest { source }
+    }
+}
+
+pub enum Source {
+    File { path: String },
+    AtCoderProblems { contest_id: String, url: String },
+}
+
+impl Source {
+    /// Accepts a path to the exported file or the URL of the contest on
+    /// AtCoder Problems.
+    pub fn parse(source: String) -> Result<Source> {
+        if !source.starts_with("http") {
+            return Ok(Source::File { path: source });
+        }
+
+        let contest_id = RE_ATCODER_PROBLEMS_URL
+            .captures(&source)
+            .map(|caps| caps["id"].to_string())
+            .ok_or_else(|| anyhow!("not a contest of AtCoder Problems: `{}`", source))?;
+        Ok(Source::AtCoderProblems {
+            contest_id,
+            url: source,
+        })
+    }
+
+    fn load(&self) -> Result<String> {
+        match self {
+            Source::File { path } => {
+                fs::read_to_string(path).with_context(|| format!("failed to read `{}`", path))
+            }
+            Source::AtCoderProblems { contest_id, .. } => {
+                let url = format!("{}/{}", ATCODER_PROBLEMS_API, contest_id);
+                http_cache::download_text(&url)
+            }
+        }
+    }
+}
+
+impl ContestProvider for VirtualContest {
+    fn site_name(&self) -> &str {
+        "Virtual contest"
+    }
+
+    fn contest_id(&self) -> &str {
+        match &self.source {
+            Source::File { path } => path,
+            Source::AtCoderProblems { contest_id, .. } => contest_id,
+        }
+    }
+
+    fn url(&self) -> &str {
+        match &self.source {
+            Source::File { path } => path,
+            Source::AtCoderProblems { url, .. } => url,
+        }
+    }
+
+    fn make_fetchers(&self) -> Result<Fetchers> {
+        let export = parse_export(&self.source.load()?)?;
+
+        // the contest of a task id cannot always be told from the id itself
+        // (e.g. `past202012_a` is in `past202012-open`).
+        let contest_ids = if export.problems.iter().any(|p| is_task_id(p)) {
+            load_contest_ids()
+                .map_err(|e| {
+                    eprintln_warning!(
+                        "failed to get the contests of the problems; guessing them from the ids: {:#}",
+                        e
+                    );
+                })
+                .ok()
+        } else {
+            None
+        };
+        let fetchers = make_fetchers(export.problems, contest_ids.as_ref());
+
+        // the problems are created in the current directory as the title is
+        // not suitable for a directory name.
+        Ok(Fetchers {
+            fetchers,
+            contest_id: self.contest_id().to_string(),
+            unique_contest_id: false,
+        })
+    }
+
+    fn start_time(&self) -> Result<Option<DateTime<FixedOffset>>> {
+        Ok(parse_export(&self.source.load()?)?.start_time)
+    }
+
+    fn session_status(&self) -> Result<Option<SessionStatus>> {
+        auth::session_status().map(Some)
+    }
+}
+
+/// Makes a fetcher for each problem. A problem which cannot be resolved
+/// fails when fetched, so that the others are still downloaded.
+fn make_fetchers(
+    problems: Vec<String>,
+    contest_ids: Option<&HashMap<String, String>>,
+) -> Vec<Fetcher> {
+    problems
+        .into_iter()
+        .enumerate()
+        .map(|(pos, problem)| {
+            let provider: Box<dyn TestCaseProvider> = match resolve(&problem, contest_ids) {
+                Ok(problem) => Box::new(fetch::AtCoder::new(problem)),
+                Err(e) => Box::new(Unresolved {
+                    problem_id: problem,
+                    error: format!("{:#}", e),
+                }),
+            };
+            Fetcher {
+                provider: Some(provider),
+                problem_name: super::local::positional_name(pos),
+            }
+        })
+        .collect()
+}
+
+fn is_task_id(problem: &str) -> bool {
+    !problem.starts_with("http") && !problem.contains('/')
+}
+
+/// Resolves a task URL or id. The contest of a task id is looked up in
+/// `contest_ids`, and guessed from the id if it is not listed there (e.g. a
+/// problem newer than the list).
+fn resolve(problem: &str, contest_ids: Option<&HashMap<String, String>>) -> Result<fetch::Problem> {
+    if problem.starts_with("http") {
+        return Ok(fetch::Problem::from_url(problem.to_string()));
+    }
+
+    match contest_ids.and_then(|ids| ids.get(problem)) {
+        Some(contest_id) if is_task_id(problem) => Ok(fetch::Problem::TaskId {
+            problem_id: problem.to_string(),
+            url: format!(
+                "{}/contests/{}/tasks/{}",
+                fetch::ATCODER_TOP,
+                contest_id,
+                problem
+            ),
+        }),
+        _ => fetch::Problem::from_problem_id(problem.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct AtCoderProblemsEntry {
+    id: String,
+    contest_id: String,
+}
+
+/// Gets the contest of every task from AtCoder Problems.
+fn load_contest_ids() -> Result<HashMap<String, String>> {
+    let text = http_cache::download_text(ATCODER_PROBLEMS_LIST)?;
+    parse_contest_ids(&text)
+}
+
+fn parse_contest_ids(text: &str) -> Result<HashMap<String, String>> {
+    let entries: Vec<AtCoderProblemsEntry> =
+        serde_json::from_str(text).context("failed to parse the problems")?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| (entry.id, entry.contest_id))
+        .collect())
+}
+
+/// A problem which could not be resolved. It fails to fetch so that it is
+/// listed among the failed problems.
+#[derive(Debug)]
+struct Unresolved {
+    problem_id: String,
+    error: String,
+}
+
+impl TestCaseProvider for Unresolved {
+    fn site_name(&self) -> &str {
+        "AtCoder"
+    }
+
+    fn problem_id(&self) -> &str {
+        &self.problem_id
+    }
+
+    fn url(&self) -> &str {
+        &self.problem_id
+    }
+
+    fn fetch_problem(&self) -> Result<FetchedProblem> {
+        Err(anyhow!("{}", self.error))
+            .with_context(|| format!("failed to resolve `{}`", self.problem_id))
+    }
+}
+
+struct Export {
+    start_time: Option<DateTime<FixedOffset>>,
+    /// task ids or task URLs in the order of the contest
+    problems: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AtCoderProblemsContest {
+    info: AtCoderProblemsInfo,
+    problems: Vec<AtCoderProblemsProblem>,
+}
+
+#[derive(Deserialize)]
+struct AtCoderProblemsInfo {
+    start_epoch_second: i64,
+}
+
+#[derive(Deserialize)]
+struct AtCoderProblemsProblem {
+    id: String,
+    order: i64,
+}
+
+fn parse_export(text: &str) -> Result<Export> {
+    let export = if text.trim_start().starts_with('{') {
+        let mut contest: AtCoderProblemsContest =
+            serde_json::from_str(text).context("failed to parse the contest")?;
+        contest.problems.sort_by_key(|p| p.order);
+        Export {
+            start_time: Utc
+                .timestamp_opt(contest.info.start_epoch_second, 0)
+                .single()
+                .map(|time| time.fixed_offset()),
+            problems: contest.problems.into_iter().map(|p| p.id).collect(),
+        }
+    } else {
+        Export {
+            start_time: None,
+            problems: text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(ToString::to_string)
+                .collect(),
+        }
+    };
+
+    ensure!(!export.problems.is_empty(), "no problems are listed");
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_export_json() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder_problems/contest.json"
+        ));
+        let export = parse_export(text).unwrap();
+        assert_eq!(
+            export.start_time.unwrap().to_rfc3339(),
+            "2023-04-29T12:00:00+00:00"
+        );
+        assert_eq!(
+            export.problems,
+            vec!["abc300_a", "abc300_b", "typical90_a", "arc058_b"]
+        );
+    }
+
+    #[test]
+    fn test_make_fetchers() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder_problems/contest.json"
+        );
+        let source = Source::parse(path.to_string()).unwrap();
+        let export = parse_export(&source.load().unwrap()).unwrap();
+        let fetchers = make_fetchers(export.problems, None);
+        let fetchers: Vec<_> = fetchers
+            .iter()
+            .map(|f| (&*f.problem_name, f.provider.as_ref().unwrap().url()))
+            .collect();
+        assert_eq!(
+            fetchers,
+            vec![
+                ("a", "https://atcoder.jp/contests/abc300/tasks/abc300_a"),
+                ("b", "https://atcoder.jp/contests/abc300/tasks/abc300_b"),
+                (
+                    "c",
+                    "https://atcoder.jp/contests/typical90/tasks/typical90_a"
+                ),
+                ("d", "https://atcoder.jp/contests/arc058/tasks/arc058_b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_export_text() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder_problems/tasks.txt"
+        ));
+        let export = parse_export(text).unwrap();
+        assert_eq!(export.start_time, None);
+        assert_eq!(
+            export.problems,
+            vec![
+                "https://atcoder.jp/contests/abc300/tasks/abc300_a",
+                "https://atcoder.jp/contests/abc300/tasks/abc300_b",
+                "https://atcoder.jp/contests/typical90/tasks/typical90_a",
+                "https://atcoder.jp/contests/abc042/tasks/arc058_b",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let contest_ids = parse_contest_ids(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder_problems/problems.json"
+        )))
+        .unwrap();
+        let problems = [
+            "past202012_a",
+            "tenka1_2018_c",
+            "abc300_a",
+            "abc999_a",
+            "???",
+        ]
+        .map(ToString::to_string)
+        .to_vec();
+        let fetchers = make_fetchers(problems, Some(&contest_ids));
+        let urls: Vec<_> = fetchers
+            .iter()
+            .map(|f| f.provider.as_ref().unwrap().url())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://atcoder.jp/contests/past202012-open/tasks/past202012_a",
+                "https://atcoder.jp/contests/tenka1-2018/tasks/tenka1_2018_c",
+                "https://atcoder.jp/contests/abc300/tasks/abc300_a",
+                // not listed yet; guessed
+                "https://atcoder.jp/contests/abc999/tasks/abc999_a",
+                "???",
+            ]
+        );
+
+        // only the unresolved one fails
+        let error = fetchers[4]
+            .provider
+            .as_ref()
+            .unwrap()
+            .fetch_problem()
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("failed to resolve `???`"));
+    }
+}
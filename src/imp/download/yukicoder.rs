@@ -0,0 +1,132 @@
+use super::local::positional_name;
+use super::{ContestProvider, Fetcher, Fetchers};
+use crate::imp::fetch::yukicoder as fetch;
+use crate::imp::fetch::yukicoder::YUKICODER_TOP;
+use anyhow::ensure;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{Html, Selector};
+
+lazy_static! {
+    static ref RE_DIRECT_URL_CONTEST_ID: Regex = Regex::new(r"contests/(?P<id>\d+)").unwrap();
+    static ref RE_PROBLEM_URL: Regex = Regex::new(r"^/problems/no/(?P<no>\d+)$").unwrap();
+}
+
+pub struct Yukicoder {
+    contest: Contest,
+}
+
+impl Yukicoder {
+    pub fn new(contest: Contest) -> Yukicoder {
+        Yukicoder { contest }
+    }
+}
+
+pub enum Contest {
+    ContestId { contest_id: String, url: String },
+    DirectUrl { url: String },
+}
+
+impl Contest {
+    pub fn from_contest_id(contest_id: String) -> Result<Contest> {
+        ensure!(
+            !contest_id.is_empty() && contest_id.chars().all(|ch| ch.is_ascii_digit()),
+            "invalid format for contest id: `{}`",
+            contest_id
+        );
+        let url = format!("{}/contests/{}", YUKICODER_TOP, contest_id);
+
+        Ok(Contest::ContestId { contest_id, url })
+    }
+
+    pub fn from_url(url: String) -> Contest {
+        Contest::DirectUrl { url }
+    }
+
+    pub fn contest_id(&self) -> &str {
+        match self {
+            Contest::ContestId { contest_id, .. } => contest_id,
+            Contest::DirectUrl { url } => match RE_DIRECT_URL_CONTEST_ID.captures(url) {
+                None => "Unknown",
+                Some(caps) => caps.name("id").unwrap().as_str(),
+            },
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            Contest::ContestId { url, .. } => url,
+            Contest::DirectUrl { url } => url,
+        }
+    }
+}
+
+impl ContestProvider for Yukicoder {
+    fn site_name(&self) -> &str {
+        "yukicoder"
+    }
+
+    fn contest_id(&self) -> &str {
+        self.contest.contest_id()
+    }
+
+    fn url(&self) -> &str {
+        self.contest.url()
+    }
+
+    fn make_fetchers(&self) -> Result<Fetchers> {
+        let text = fetch::download_text(self.url()).context("failed to get the contest page")?;
+        let problems = parse_problems(&text);
+
+        // yukicoder identifies problems by numbers, so name them by the order.
+        let fetchers = problems
+            .into_iter()
+            .enumerate()
+            .map(|(pos, no)| {
+                let problem =
+                    fetch::Problem::from_url(format!("{}/problems/no/{}", YUKICODER_TOP, no));
+                Fetcher {
+                    provider: Some(Box::new(fetch::Yukicoder::new(problem))),
+                    problem_name: positional_name(pos),
+                }
+            })
+            .collect_vec();
+
+        Ok(Fetchers {
+            fetchers,
+            contest_id: self.contest_id().to_string(),
+            unique_contest_id: true,
+        })
+    }
+}
+
+/// Gets the problem numbers listed in the problem table of the contest page.
+fn parse_problems(text: &str) -> Vec<String> {
+    let document = Html::parse_document(text);
+    let sel_link = Selector::parse("table a[href]").unwrap();
+
+    document
+        .select(&sel_link)
+        .filter_map(|a| {
+            let caps = RE_PROBLEM_URL.captures(a.value().attr("href")?)?;
+            Some(caps["no"].to_string())
+        })
+        .unique()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_problems() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/yukicoder/contest.html"
+        ));
+        assert_eq!(parse_problems(text), ["1535", "1536", "1537"]);
+    }
+}
@@ -1,8 +1,29 @@
+use super::FetchedProblem;
 use crate::imp::auth::aoj as auth;
+use crate::imp::http_cache;
+use crate::imp::problem_info::{self, ProblemInfo};
+use crate::imp::statement;
 use crate::imp::test_case::TestCaseFile;
-use anyhow::ensure;
+use anyhow::{anyhow, ensure};
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::StatusCode;
 use scraper::{Html, Selector};
+use serde_derive::Deserialize;
+
+pub const AOJ_API_TOP: &str = "https://judgeapi.u-aizu.ac.jp";
+const AOJ_DAT_TOP: &str = "https://judgedat.u-aizu.ac.jp";
+const AOJ_TOP: &str = "https://onlinejudge.u-aizu.ac.jp";
+
+const STATEMENT_SELECTORS: [&str; 2] = ["div.description", "body"];
+
+lazy_static! {
+    // `0000` for volumes, `ITP1_1_A` or `DSL_2_A` for courses
+    static ref RE_PROBLEM_ID: Regex = Regex::new(r"^[A-Za-z0-9_]+$").unwrap();
+    static ref RE_DIRECT_URL_PROBLEM_ID: Regex =
+        Regex::new(r"(?:problems/|[?&]id=)(?P<id>[A-Za-z0-9_]+)").unwrap();
+}
 
 #[derive(Debug)]
 pub struct Aoj {
@@ -26,11 +47,13 @@ impl Problem {
         let problem = if problem_id.starts_with("http") {
             Problem::DirectUrl { url: problem_id }
         } else {
+            ensure!(
+                RE_PROBLEM_ID.is_match(&problem_id),
+                "invalid format for problem id: {}",
+                problem_id
+            );
             Problem::ProblemId {
-                url: format!(
-                    "http://judge.u-aizu.ac.jp/onlinejudge/description.jsp?lang=jp&id={}",
-                    problem_id,
-                ),
+                url: format!("{}/problems/{}", AOJ_TOP, problem_id),
                 problem_id,
             }
         };
@@ -40,7 +63,10 @@ impl Problem {
     pub fn problem_id(&self) -> &str {
         match *self {
             Problem::ProblemId { ref problem_id, .. } => problem_id,
-            Problem::DirectUrl { .. } => "Unknown",
+            Problem::DirectUrl { ref url } => match RE_DIRECT_URL_PROBLEM_ID.captures(url) {
+                None => "Unknown",
+                Some(caps) => caps.name("id").unwrap().as_str(),
+            },
         }
     }
 
@@ -65,19 +91,148 @@ impl super::TestCaseProvider for Aoj {
         self.problem.url()
     }
 
-    fn fetch_test_case_files(&self) -> Result<Vec<TestCaseFile>> {
-        let text = download_text(self.problem.url())
-            .with_context(|| format!("failed to fetch a problem: {}", self.problem.problem_id()))?;
+    fn fetch_problem(&self) -> Result<FetchedProblem> {
+        let samples = match self.problem.problem_id() {
+            "Unknown" => None,
+            problem_id => match fetch_samples(problem_id) {
+                Ok(samples) => Some(samples).filter(|s| !s.is_empty()),
+                Err(e) if is_not_found(&e) => None,
+                Err(e) => return Err(e).context("failed to fetch the samples"),
+            },
+        };
+
+        // the API does not know some problems; scrape the problem page in that
+        // case.
+        match samples {
+            Some(samples) => {
+                // the samples are already fetched; the description is optional.
+                let (statement, info) = fetch_description(self.problem.problem_id())
+                    .map(|(statement, info)| (Some(statement), info))
+                    .unwrap_or_default();
+                Ok(FetchedProblem {
+                    test_cases: make_test_case_files(samples)?,
+                    statement,
+                    info,
+                })
+            }
+            None => {
+                let url = match &self.problem {
+                    Problem::ProblemId { problem_id, .. } => format!(
+                        "http://judge.u-aizu.ac.jp/onlinejudge/description.jsp?lang=jp&id={}",
+                        problem_id
+                    ),
+                    Problem::DirectUrl { url } => url.clone(),
+                };
+                let text = download_text(&url).with_context(|| {
+                    format!("failed to fetch a problem: {}", self.problem.problem_id())
+                })?;
+                parse_problem(&text)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Description {
+    html: String,
+    /// in seconds
+    time_limit: Option<f64>,
+    /// in kilobytes
+    memory_limit: Option<u64>,
+}
+
+fn fetch_description(problem_id: &str) -> Result<(String, ProblemInfo)> {
+    let url = format!("{}/resources/descriptions/ja/{}", AOJ_API_TOP, problem_id);
+    let text = download_text(&url)?;
+    parse_description_json(&text)
+}
+
+fn parse_description_json(text: &str) -> Result<(String, ProblemInfo)> {
+    let description: Description =
+        serde_json::from_str(text).context("failed to parse the description")?;
+    let statement = statement::extract(&description.html, &["body"])
+        .ok_or_else(|| anyhow!("the description is empty"))?;
 
-        parse_text(text)
+    let mut info = problem_info::parse_page(&description.html, "h1", &["body"]);
+    if let Some(time_limit) = description.time_limit {
+        info.time_limit_milliseconds = Some((time_limit * 1000.0).round() as u64);
+    }
+    if let Some(memory_limit) = description.memory_limit {
+        info.memory_limit_megabytes = Some(memory_limit / 1024);
     }
+
+    Ok((statement, info))
+}
+
+/// Parses everything on the problem page.
+pub fn parse_problem(text: &str) -> Result<FetchedProblem> {
+    Ok(FetchedProblem {
+        test_cases: parse_text(text)?,
+        statement: parse_statement(text),
+        info: problem_info::parse_page(text, "h1", &STATEMENT_SELECTORS),
+    })
+}
+
+pub fn parse_statement(text: &str) -> Option<String> {
+    statement::extract(text, &STATEMENT_SELECTORS)
 }
 
-pub fn parse_text(text: String) -> Result<Vec<TestCaseFile>> {
-    let document = Html::parse_document(&text);
+/// Parses the problem page.
+pub fn parse_text(text: &str) -> Result<Vec<TestCaseFile>> {
+    parse_description(text).and_then(make_test_case_files)
+}
+
+fn make_test_case_files(samples: Vec<(String, String)>) -> Result<Vec<TestCaseFile>> {
+    let idx_start = TestCaseFile::next_unused_idx().context("failed to get unused index")?;
+    Ok(samples
+        .into_iter()
+        .zip(idx_start..)
+        .map(|((input, output), idx)| TestCaseFile::new_with_idx(idx, input, output))
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct Sample {
+    serial: i32,
+    #[serde(rename = "in")]
+    input: String,
+    #[serde(rename = "out")]
+    output: String,
+}
+
+fn fetch_samples(problem_id: &str) -> Result<Vec<(String, String)>> {
+    let url = format!("{}/testcases/samples/{}", AOJ_DAT_TOP, problem_id);
+    let text = download_text(&url)?;
+    parse_samples(&text)
+}
+
+/// Parses the response of the sample API, which is a list of samples.
+fn parse_samples(text: &str) -> Result<Vec<(String, String)>> {
+    let mut samples: Vec<Sample> =
+        serde_json::from_str(text).context("failed to parse the samples")?;
+    samples.sort_by_key(|s| s.serial);
+
+    Ok(samples
+        .into_iter()
+        .map(|s| {
+            (
+                super::normalize_sample(&s.input),
+                super::normalize_sample(&s.output),
+            )
+        })
+        .collect())
+}
+
+/// Extracts samples from the problem page. The first `<pre>` is the input
+/// format if the number of them is odd.
+fn parse_description(text: &str) -> Result<Vec<(String, String)>> {
+    let document = Html::parse_document(text);
     let sel_pre = Selector::parse("pre").unwrap();
 
-    let mut pres: Vec<_> = document.select(&sel_pre).collect();
+    let mut pres: Vec<_> = document
+        .select(&sel_pre)
+        .map(|pre| super::normalize_sample(&pre.text().collect::<String>()))
+        .collect();
     ensure!(
         pres.len() > 1,
         "unexpected number of <pre>: {} found",
@@ -85,26 +240,74 @@ pub fn parse_text(text: String) -> Result<Vec<TestCaseFile>> {
     );
 
     if pres.len() % 2 == 1 {
-        pres = pres.into_iter().skip(1).collect();
+        pres.remove(0);
     }
 
-    let mut result = Vec::new();
-    let beginning = TestCaseFile::next_unused_idx().context("failed to get unused index")?;
-    for i in 0..(pres.len() / 2) {
-        let tsf = TestCaseFile::new_with_idx(
-            beginning + i as i32,
-            pres[i * 2].inner_html(),
-            pres[i * 2 + 1].inner_html(),
+    Ok(pres
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect())
+}
+
+fn is_not_found(e: &anyhow::Error) -> bool {
+    e.chain()
+        .filter_map(|e| e.downcast_ref::<reqwest::Error>())
+        .any(|e| e.status() == Some(StatusCode::NOT_FOUND))
+}
+
+pub fn download_text(url: &str) -> Result<String> {
+    http_cache::get_text(url, |headers| {
+        auth::authenticated_get(url, headers)
+            .with_context(|| format!("failed to get {} with logged in", url))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_samples() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/aoj/samples.json"
+        ));
+        let samples = parse_samples(text).unwrap();
+        assert_eq!(
+            samples,
+            vec![
+                ("2\n".to_string(), "8\n".to_string()),
+                ("3\n".to_string(), "27\n".to_string()),
+            ]
         );
-        result.push(tsf);
     }
 
-    Ok(result)
-}
+    #[test]
+    fn test_parse_description_json() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/aoj/description.json"
+        ));
+        let (statement, info) = parse_description_json(text).unwrap();
+        assert!(statement.starts_with("<h1>X Cubic</h1>"));
+        assert_eq!(info.title.as_deref(), Some("X Cubic"));
+        assert_eq!(info.time_limit_milliseconds, Some(1000));
+        assert_eq!(info.memory_limit_megabytes, Some(128));
+    }
 
-fn download_text(url: &str) -> Result<String> {
-    auth::authenticated_get(url)
-        .with_context(|| format!("failed to get {} with logged in", url))?
-        .text()
-        .context("failed to get text")
+    #[test]
+    fn test_parse_description() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/aoj/description.html"
+        ));
+        let samples = parse_description(text).unwrap();
+        assert_eq!(
+            samples,
+            vec![
+                ("2\n".to_string(), "8\n".to_string()),
+                ("3\n".to_string(), "27\n".to_string()),
+            ]
+        );
+    }
 }
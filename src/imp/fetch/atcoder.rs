@@ -1,12 +1,36 @@
-use super::TestCaseProvider;
+use super::{normalize_sample, FetchedProblem, TestCaseProvider};
 use crate::imp::auth::atcoder as auth;
+use crate::imp::http_cache;
+use crate::imp::problem_info::{self, ProblemInfo};
+use crate::imp::statement;
 use crate::imp::test_case::TestCaseFile;
-use anyhow::ensure;
+use anyhow::{anyhow, bail};
 use anyhow::{Context, Result};
-use easy_scraper::Pattern;
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::BTreeMap;
 
 pub const ATCODER_TOP: &str = "https://atcoder.jp";
 
+const STATEMENT_SELECTORS: [&str; 3] = [
+    "#task-statement span.lang-ja",
+    "#task-statement span.lang-en",
+    "#task-statement",
+];
+
+lazy_static! {
+    static ref RE_CONTEST_TASK: Regex =
+        Regex::new(r"^(?P<contest_id>[A-Za-z0-9_-]+)/(?P<task>[A-Za-z0-9]+)$").unwrap();
+    static ref RE_TASK_ID: Regex = Regex::new(r"^(?P<contest_id>[a-z0-9-]+)_[a-z0-9]+$").unwrap();
+    static ref RE_ABC_STYLE_ID: Regex =
+        Regex::new(r"^(?P<contest_id>[a-z]{3}\d{3})(?P<task>[a-z]+)$").unwrap();
+    static ref RE_SAMPLE_INPUT: Regex =
+        Regex::new(r"^(?:入力例|Sample Input)\s*(?P<n>\d+)").unwrap();
+    static ref RE_SAMPLE_OUTPUT: Regex =
+        Regex::new(r"^(?:出力例|Sample Output)\s*(?P<n>\d+)").unwrap();
+}
+
 #[derive(Debug)]
 pub struct AtCoder {
     problem: Problem,
@@ -20,11 +44,14 @@ impl AtCoder {
 
 #[derive(Debug)]
 pub enum Problem {
-    ProblemId {
+    TaskId {
+        problem_id: String,
+        url: String,
+    },
+    ContestTask {
         problem_id: String,
-        contest_name: String,
         contest_id: String,
-        problem: String,
+        task: String,
         url: String,
     },
     DirectUrl {
@@ -33,29 +60,38 @@ pub enum Problem {
 }
 
 impl Problem {
+    /// Parses the problem id. The following forms are accepted:
+    ///
+    /// - `abc092a`: an ABC-style contest id (three letters and three digits)
+    ///   followed by the task
+    /// - `typical90_a`: the task id in the URL, prefixed by its contest id
+    /// - `typical90/a`: the contest id and the task name in the task list;
+    ///   the task URL is resolved from the task list when fetching
     pub fn from_problem_id(problem_id: String) -> Result<Problem> {
-        ensure!(
-            problem_id.len() == 7,
-            "invalid format for problem id: {}",
-            problem_id
-        );
+        if let Some(caps) = RE_CONTEST_TASK.captures(&problem_id) {
+            let contest_id = caps["contest_id"].to_string();
+            let task = caps["task"].to_ascii_lowercase();
+            let url = tasks_url(&contest_id);
+            return Ok(Problem::ContestTask {
+                problem_id,
+                contest_id,
+                task,
+                url,
+            });
+        }
 
-        let contest_name = problem_id[0..3].to_string();
-        let contest_id = problem_id[0..6].to_string();
-        let problem = problem_id[6..7].to_string();
-        let url = format!(
-            "{}/contests/{}/tasks/{}_{}",
-            ATCODER_TOP, contest_id, contest_id, problem
-        );
-        let problem = Problem::ProblemId {
-            problem_id,
-            contest_name,
-            contest_id,
-            problem,
-            url,
+        let (contest_id, task_id) = if let Some(caps) = RE_TASK_ID.captures(&problem_id) {
+            (caps["contest_id"].to_string(), problem_id.to_string())
+        } else if let Some(caps) = RE_ABC_STYLE_ID.captures(&problem_id) {
+            let contest_id = caps["contest_id"].to_string();
+            let task_id = format!("{}_{}", contest_id, &caps["task"]);
+            (contest_id, task_id)
+        } else {
+            bail!("invalid format for problem id: {}", problem_id);
         };
 
-        Ok(problem)
+        let url = format!("{}/contests/{}/tasks/{}", ATCODER_TOP, contest_id, task_id);
+        Ok(Problem::TaskId { problem_id, url })
     }
 
     pub fn from_url(url: String) -> Problem {
@@ -64,17 +100,39 @@ impl Problem {
 
     pub fn problem_id(&self) -> &str {
         match self {
-            Problem::ProblemId { problem_id, .. } => problem_id,
+            Problem::TaskId { problem_id, .. } => problem_id,
+            Problem::ContestTask { problem_id, .. } => problem_id,
             Problem::DirectUrl { .. } => "Unknown",
         }
     }
 
     pub fn url(&self) -> &str {
         match self {
-            Problem::ProblemId { url, .. } => url,
+            Problem::TaskId { url, .. } => url,
+            Problem::ContestTask { url, .. } => url,
             Problem::DirectUrl { url } => url,
         }
     }
+
+    /// Gets the URL of the task page, looking up the task list if needed.
+    fn task_url(&self) -> Result<String> {
+        match self {
+            Problem::ContestTask {
+                contest_id,
+                task,
+                url,
+                ..
+            } => {
+                let text = download_text(url)?;
+                parse_task_list(&text)
+                    .into_iter()
+                    .find(|row| row.problem.eq_ignore_ascii_case(task))
+                    .map(|row| format!("{}{}", ATCODER_TOP, row.url))
+                    .ok_or_else(|| anyhow!("task `{}` not found in contest `{}`", task, contest_id))
+            }
+            _ => Ok(self.url().to_string()),
+        }
+    }
 }
 
 impl TestCaseProvider for AtCoder {
@@ -90,54 +148,280 @@ impl TestCaseProvider for AtCoder {
         self.problem.url()
     }
 
-    fn fetch_test_case_files(&self) -> Result<Vec<TestCaseFile>> {
-        let text = download_text(self.problem.url())?;
-        parse_text(&text)
-    }
-}
-
-fn download_text(url: &str) -> Result<String> {
-    auth::authenticated_get(url)
-        .with_context(|| format!("failed to get `{}` with login", url))?
-        .text()
-        .context("failed to get the text")
-}
-
-fn parse_text(text: &str) -> Result<Vec<TestCaseFile>> {
-    let pattern = Pattern::new(
-        r#"
-<div id="task-statement">
-    <span class="lang-ja">
-        <div class="part">
-        <section>
-            <h3>入力例 {{n}}</h3>
-            <pre>
-                {{input}}
-            </pre>
-        </section>
-        </div>
-        <div class="part">
-        <section>
-            <h3>出力例 {{n}}</h3>
-            <pre>
-                {{output}}
-            </pre>
-        </section>
-        </div>
-    </span>
-</div>
-"#,
-    )
-    .unwrap();
+    fn fetch_problem(&self) -> Result<FetchedProblem> {
+        let url = self
+            .problem
+            .task_url()
+            .context("failed to resolve the task")?;
+        let text = download_text(&url)?;
+        parse_problem(&text)
+    }
+}
+
+/// Gets the ABC-style problem id (like `abc092a`) of the task if it can be
+/// derived without looking up the task list.
+pub fn abc_style_problem_id(contest_id: &str, task: &str) -> Option<String> {
+    let problem_id = format!("{}{}", contest_id, task.to_ascii_lowercase());
+    RE_ABC_STYLE_ID.is_match(&problem_id).then_some(problem_id)
+}
+
+pub fn tasks_url(contest_id: &str) -> String {
+    format!("{}/contests/{}/tasks", ATCODER_TOP, contest_id)
+}
+
+pub fn download_text(url: &str) -> Result<String> {
+    http_cache::get_text(url, |headers| {
+        auth::authenticated_get(url, headers)
+            .with_context(|| format!("failed to get `{}` with login", url))
+    })
+}
+
+pub struct TaskListRow {
+    pub problem: String,
+    pub url: String,
+}
+
+/// Parses the task list page (`/contests/{contest_id}/tasks`).
+pub fn parse_task_list(text: &str) -> Vec<TaskListRow> {
+    let document = Html::parse_document(text);
+    let sel_link = Selector::parse("table tbody tr td:first-child a[href]").unwrap();
 
+    document
+        .select(&sel_link)
+        .map(|a| TaskListRow {
+            problem: a.text().collect::<String>().trim().to_string(),
+            url: a.value().attr("href").unwrap().to_string(),
+        })
+        .collect()
+}
+
+pub fn parse_text(text: &str) -> Result<Vec<TestCaseFile>> {
     let idx_start = TestCaseFile::next_unused_idx().context("failed to get unused index")?;
-    Ok(pattern
-        .matches(text)
+    Ok(parse_samples(text)
         .into_iter()
-        .enumerate()
-        .map(|(i, case)| {
-            let idx = idx_start + i as i32;
-            TestCaseFile::new_with_idx(idx, case["input"].clone(), case["output"].clone())
-        })
+        .zip(idx_start..)
+        .map(|((input, output), idx)| TestCaseFile::new_with_idx(idx, input, output))
         .collect())
 }
+
+/// Parses everything on the problem page.
+pub fn parse_problem(text: &str) -> Result<FetchedProblem> {
+    Ok(FetchedProblem {
+        test_cases: parse_text(text)?,
+        statement: parse_statement(text),
+        info: parse_info(text),
+    })
+}
+
+pub fn parse_info(text: &str) -> ProblemInfo {
+    problem_info::parse_page(text, "span.h2", &STATEMENT_SELECTORS)
+}
+
+/// Extracts the statement in the same language as the samples.
+pub fn parse_statement(text: &str) -> Option<String> {
+    statement::extract(text, &STATEMENT_SELECTORS)
+}
+
+/// Extracts pairs of the sample input and output. The Japanese statement is
+/// preferred, and the English one is used if no samples are found in it. Old
+/// problems have no language blocks, so the whole statement is used at last.
+fn parse_samples(text: &str) -> Vec<(String, String)> {
+    let document = Html::parse_document(text);
+    STATEMENT_SELECTORS
+        .iter()
+        .filter_map(|sel| document.select(&Selector::parse(sel).unwrap()).next())
+        .map(parse_samples_in)
+        .find(|samples| !samples.is_empty())
+        .unwrap_or_default()
+}
+
+/// Collects samples in sections headed `入力例 N` / `出力例 N` (or `Sample Input
+/// N` / `Sample Output N`). Inputs and outputs are paired by the number `N`
+/// since they are not always placed alternately.
+fn parse_samples_in(statement: ElementRef) -> Vec<(String, String)> {
+    let sel_section = Selector::parse("section").unwrap();
+    let sel_heading = Selector::parse("h3").unwrap();
+    let sel_pre = Selector::parse("pre").unwrap();
+
+    let mut inputs = BTreeMap::new();
+    let mut outputs = BTreeMap::new();
+    for section in statement.select(&sel_section) {
+        let heading = match section.select(&sel_heading).next() {
+            Some(heading) => heading.text().collect::<String>(),
+            None => continue,
+        };
+        let pre = match section.select(&sel_pre).next() {
+            Some(pre) => normalize_sample(&pre.text().collect::<String>()),
+            None => continue,
+        };
+
+        if let Some(caps) = RE_SAMPLE_INPUT.captures(heading.trim()) {
+            inputs.insert(caps["n"].parse::<u32>().unwrap_or(0), pre);
+        } else if let Some(caps) = RE_SAMPLE_OUTPUT.captures(heading.trim()) {
+            outputs.insert(caps["n"].parse::<u32>().unwrap_or(0), pre);
+        }
+    }
+
+    inputs
+        .into_iter()
+        .filter_map(|(n, input)| Some((input, outputs.remove(&n)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_id() {
+        let url = |id: &str| {
+            Problem::from_problem_id(id.to_string())
+                .unwrap()
+                .url()
+                .to_string()
+        };
+        assert_eq!(
+            url("abc092a"),
+            "https://atcoder.jp/contests/abc092/tasks/abc092_a"
+        );
+        assert_eq!(
+            url("abc300ex"),
+            "https://atcoder.jp/contests/abc300/tasks/abc300_ex"
+        );
+        assert_eq!(
+            url("abc300_ex"),
+            "https://atcoder.jp/contests/abc300/tasks/abc300_ex"
+        );
+        assert_eq!(
+            url("practice2_a"),
+            "https://atcoder.jp/contests/practice2/tasks/practice2_a"
+        );
+        assert_eq!(
+            url("past202012-open/a"),
+            "https://atcoder.jp/contests/past202012-open/tasks"
+        );
+        assert_eq!(
+            url("apc001a"),
+            "https://atcoder.jp/contests/apc001/tasks/apc001_a"
+        );
+        assert!(Problem::from_problem_id("abc".to_string()).is_err());
+        assert_eq!(
+            abc_style_problem_id("abc086", "A").as_deref(),
+            Some("abc086a")
+        );
+        assert_eq!(abc_style_problem_id("typical90", "a"), None);
+    }
+
+    #[test]
+    fn test_parse_task_list() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder/tasks.html"
+        ));
+        let rows = parse_task_list(text)
+            .into_iter()
+            .map(|row| (row.problem, row.url))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                ("A", "/contests/typical90/tasks/typical90_a"),
+                ("B", "/contests/typical90/tasks/typical90_b"),
+                ("BL", "/contests/typical90/tasks/typical90_bl"),
+            ]
+            .map(|(p, u)| (p.to_string(), u.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_modern() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder/problem_modern.html"
+        ));
+        let samples = parse_samples(text);
+        assert_eq!(
+            samples,
+            vec![
+                ("3 4\n".to_string(), "Even\n".to_string()),
+                ("1 21\n".to_string(), "Odd\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder/problem_modern.html"
+        ));
+        let info = parse_info(text);
+        assert_eq!(info.title.as_deref(), Some("A - Product"));
+        assert_eq!(info.time_limit_milliseconds, Some(2000));
+        assert_eq!(info.memory_limit_megabytes, Some(256));
+        assert!(!info.interactive && !info.special_judge);
+    }
+
+    /// The page of practice_1 with the Japanese statement removed, standing in
+    /// for a problem whose statement is only in English.
+    #[test]
+    fn test_parse_english_only() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder/problem_english_only.html"
+        ));
+        let samples = parse_samples(text);
+        assert_eq!(
+            samples,
+            vec![
+                ("1\n2 3\ntest\n".to_string(), "6 test\n".to_string()),
+                (
+                    "72\n128 256\nmyonmyon\n".to_string(),
+                    "456 myonmyon\n".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_old_format() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/atcoder/problem_old_format.html"
+        ));
+        let samples = parse_samples(text);
+        assert_eq!(
+            samples,
+            vec![
+                ("15\n10\n".to_string(), "5\n".to_string()),
+                ("0\n0\n".to_string(), "0\n".to_string()),
+                ("5\n20\n".to_string(), "-15\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_out_of_order() {
+        // the inputs are listed before the outputs
+        let text = r#"<div id="task-statement"><span class="lang"><span class="lang-ja">
+            <div class="part"><section><h3>入力例 1</h3><pre>1
+</pre></section></div>
+            <div class="part"><section><h3>入力例 2</h3><pre>2
+</pre></section></div>
+            <div class="part"><section><h3>出力例 2</h3><pre>4
+</pre></section></div>
+            <div class="part"><section><h3>出力例 1</h3><pre>1
+</pre></section></div>
+            <div class="part"><section><h3>入力例 3</h3><pre>3
+</pre></section></div>
+        </span></span></div>"#;
+        let samples = parse_samples(text);
+        assert_eq!(
+            samples,
+            vec![
+                ("1\n".to_string(), "1\n".to_string()),
+                ("2\n".to_string(), "4\n".to_string()),
+            ]
+        );
+    }
+}
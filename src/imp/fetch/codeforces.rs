@@ -0,0 +1,218 @@
+use super::{FetchedProblem, TestCaseProvider};
+use crate::imp::http_cache;
+use crate::imp::problem_info::{self, ProblemInfo};
+use crate::imp::statement;
+use crate::imp::test_case::TestCaseFile;
+use anyhow::anyhow;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+
+pub const CODEFORCES_TOP: &str = "https://codeforces.com";
+
+lazy_static! {
+    static ref RE_PROBLEM_ID: Regex =
+        Regex::new(r"^(?P<contest_id>\d+)(?P<problem>[A-Za-z]\d*)$").unwrap();
+}
+
+#[derive(Debug)]
+pub struct Codeforces {
+    problem: Problem,
+}
+
+impl Codeforces {
+    pub fn new(problem: Problem) -> Codeforces {
+        Codeforces { problem }
+    }
+}
+
+#[derive(Debug)]
+pub enum Problem {
+    ProblemId { problem_id: String, url: String },
+    DirectUrl { url: String },
+}
+
+impl Problem {
+    /// Parses the problem id like `1234A`; the contest id followed by the
+    /// problem index.
+    pub fn from_problem_id(problem_id: String) -> Result<Problem> {
+        let caps = RE_PROBLEM_ID
+            .captures(&problem_id)
+            .ok_or_else(|| anyhow!("invalid format for problem id: {}", problem_id))?;
+        let url = format!(
+            "{}/contest/{}/problem/{}",
+            CODEFORCES_TOP,
+            &caps["contest_id"],
+            caps["problem"].to_ascii_uppercase()
+        );
+
+        Ok(Problem::ProblemId { problem_id, url })
+    }
+
+    pub fn from_url(url: String) -> Problem {
+        Problem::DirectUrl { url }
+    }
+
+    pub fn problem_id(&self) -> &str {
+        match self {
+            Problem::ProblemId { problem_id, .. } => problem_id,
+            Problem::DirectUrl { .. } => "Unknown",
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            Problem::ProblemId { url, .. } => url,
+            Problem::DirectUrl { url } => url,
+        }
+    }
+}
+
+impl TestCaseProvider for Codeforces {
+    fn site_name(&self) -> &str {
+        "Codeforces"
+    }
+
+    fn problem_id(&self) -> &str {
+        self.problem.problem_id()
+    }
+
+    fn url(&self) -> &str {
+        self.problem.url()
+    }
+
+    fn fetch_problem(&self) -> Result<FetchedProblem> {
+        let text = download_text(self.problem.url())?;
+        parse_problem(&text)
+    }
+}
+
+pub fn download_text(url: &str) -> Result<String> {
+    http_cache::download_text(url)
+}
+
+/// Parses everything on the problem page.
+pub fn parse_problem(text: &str) -> Result<FetchedProblem> {
+    Ok(FetchedProblem {
+        test_cases: parse_text(text)?,
+        statement: parse_statement(text),
+        info: parse_info(text),
+    })
+}
+
+pub fn parse_info(text: &str) -> ProblemInfo {
+    problem_info::parse_page(
+        text,
+        ".problem-statement .title",
+        &["div.problem-statement"],
+    )
+}
+
+pub fn parse_statement(text: &str) -> Option<String> {
+    statement::extract(text, &["div.problem-statement"])
+}
+
+pub fn parse_text(text: &str) -> Result<Vec<TestCaseFile>> {
+    let idx_start = TestCaseFile::next_unused_idx().context("failed to get unused index")?;
+    Ok(parse_samples(text)
+        .into_iter()
+        .zip(idx_start..)
+        .map(|((input, output), idx)| TestCaseFile::new_with_idx(idx, input, output))
+        .collect())
+}
+
+/// Extracts pairs of the sample input and output. A `.sample-test` block may
+/// contain several pairs of `.input` and `.output`.
+fn parse_samples(text: &str) -> Vec<(String, String)> {
+    let document = Html::parse_document(text);
+    let sel_input = Selector::parse("div.sample-test div.input pre").unwrap();
+    let sel_output = Selector::parse("div.sample-test div.output pre").unwrap();
+
+    let inputs = document.select(&sel_input).map(pre_text);
+    let outputs = document.select(&sel_output).map(pre_text);
+
+    inputs.zip(outputs).collect()
+}
+
+/// Gets the text in `<pre>`. Older problems separate lines by `<br />` and newer
+/// ones wrap each line of the input in `<div class="test-example-line">`.
+fn pre_text(pre: ElementRef) -> String {
+    let mut text = String::new();
+    for child in pre.children() {
+        match child.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.name() == "br" => text.push('\n'),
+            Node::Element(_) => {
+                if let Some(elem) = ElementRef::wrap(child) {
+                    text.extend(elem.text());
+                    text.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    super::normalize_sample(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_old_format() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/codeforces/problem_old_format.html"
+        ));
+        let samples = parse_samples(text);
+        assert_eq!(
+            samples,
+            vec![(
+                "3\n5\n1 2 3 4 5\n3\n1 2 2\n4\n1 1 1 1\n".to_string(),
+                "3\n2\n1\n".to_string()
+            )]
+        );
+    }
+
+    /// The fixture is hand-written after the markup of the multi-`div`
+    /// samples, not saved from the site.
+    #[test]
+    fn test_parse_new_format() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/codeforces/problem_new_format.html"
+        ));
+        let samples = parse_samples(text);
+        assert_eq!(
+            samples,
+            vec![
+                ("2\n1 2\n3 4\n".to_string(), "3\n7\n".to_string()),
+                ("1\n-5 <5>\n".to_string(), "0\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/codeforces/problem_new_format.html"
+        ));
+        let info = parse_info(text);
+        assert_eq!(info.title.as_deref(), Some("B. Sum of Pairs"));
+        assert_eq!(info.time_limit_milliseconds, Some(2000));
+        assert_eq!(info.memory_limit_megabytes, Some(512));
+    }
+
+    #[test]
+    fn test_problem_id() {
+        let problem = Problem::from_problem_id("1234b1".to_string()).unwrap();
+        assert_eq!(
+            problem.url(),
+            "https://codeforces.com/contest/1234/problem/B1"
+        );
+        assert!(Problem::from_problem_id("abc".to_string()).is_err());
+    }
+}
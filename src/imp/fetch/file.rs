@@ -0,0 +1,151 @@
+use super::{aoj, atcoder, codeforces, yukicoder, FetchedProblem, TestCaseProvider};
+use anyhow::{anyhow, bail};
+use anyhow::{Context, Result};
+use scraper::{Html, Selector};
+use std::fs;
+use std::io::{self, Read};
+
+/// A provider which parses a locally saved problem page (or the standard
+/// input if the path is `-`) with the parser of the site.
+#[derive(Debug)]
+pub struct File {
+    path: String,
+    site: Option<&'static str>,
+}
+
+impl File {
+    /// Creates the provider. The site is detected from the document if not
+    /// specified.
+    pub fn new(path: String, site: Option<&str>) -> Result<File> {
+        let site = site
+            .map(|site| normalize_site(site).ok_or_else(|| anyhow!("unknown site: `{}`", site)))
+            .transpose()?;
+
+        Ok(File { path, site })
+    }
+}
+
+impl TestCaseProvider for File {
+    fn site_name(&self) -> &str {
+        "Local file"
+    }
+
+    fn problem_id(&self) -> &str {
+        "Unknown"
+    }
+
+    fn url(&self) -> &str {
+        if self.path == "-" {
+            "stdin"
+        } else {
+            &self.path
+        }
+    }
+
+    fn fetch_problem(&self) -> Result<FetchedProblem> {
+        let text = if self.path == "-" {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .context("failed to read from stdin")?;
+            text
+        } else {
+            fs::read_to_string(&self.path)
+                .with_context(|| format!("failed to read `{}`", self.path))?
+        };
+
+        let site = match self.site {
+            Some(site) => site,
+            None => detect_site(&text).ok_or_else(|| {
+                anyhow!("failed to detect the site of the document; specify it with `--site`")
+            })?,
+        };
+
+        match site {
+            "aoj" => aoj::parse_problem(&text),
+            "atcoder" => atcoder::parse_problem(&text),
+            "codeforces" => codeforces::parse_problem(&text),
+            "yukicoder" => yukicoder::parse_problem(&text),
+            site => bail!("internal error: unknown site `{}`", site),
+        }
+    }
+}
+
+fn normalize_site(site: &str) -> Option<&'static str> {
+    match site {
+        "aoj" => Some("aoj"),
+        "atcoder" | "at" => Some("atcoder"),
+        "codeforces" | "cf" => Some("codeforces"),
+        "yukicoder" | "yuki" => Some("yukicoder"),
+        _ => None,
+    }
+}
+
+/// Guesses the site from the title of the document, or from the markup
+/// around the samples if the title does not tell.
+fn detect_site(text: &str) -> Option<&'static str> {
+    let document = Html::parse_document(text);
+    let title = document
+        .select(&Selector::parse("title").unwrap())
+        .next()
+        .map(|title| title.text().collect::<String>())
+        .unwrap_or_default();
+
+    let by_title = [
+        ("Aizu Online Judge", "aoj"),
+        ("AtCoder", "atcoder"),
+        ("Codeforces", "codeforces"),
+        ("yukicoder", "yukicoder"),
+    ];
+    if let Some(&(_, site)) = by_title.iter().find(|(name, _)| title.contains(name)) {
+        return Some(site);
+    }
+
+    let by_markup = [
+        ("#task-statement", "atcoder"),
+        ("div.sample-test", "codeforces"),
+        ("div.sample", "yukicoder"),
+    ];
+    by_markup
+        .iter()
+        .find(|(sel, _)| {
+            document
+                .select(&Selector::parse(sel).unwrap())
+                .next()
+                .is_some()
+        })
+        .map(|&(_, site)| site)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_site() {
+        macro_rules! fixture {
+            ($name:literal) => {
+                include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/", $name))
+            };
+        }
+
+        assert_eq!(detect_site(fixture!("aoj/description.html")), Some("aoj"));
+        assert_eq!(
+            detect_site(fixture!("atcoder/problem_modern.html")),
+            Some("atcoder")
+        );
+        assert_eq!(
+            detect_site(fixture!("atcoder/problem_english_only.html")),
+            Some("atcoder")
+        );
+        assert_eq!(
+            detect_site(fixture!("codeforces/problem_new_format.html")),
+            Some("codeforces")
+        );
+        assert_eq!(
+            detect_site(fixture!("yukicoder/problem.html")),
+            Some("yukicoder")
+        );
+        assert_eq!(detect_site(fixture!("generic/problem.html")), None);
+    }
+}
@@ -0,0 +1,143 @@
+use super::{FetchedProblem, TestCaseProvider};
+use crate::imp::config::{SiteSelectors, CONFIG};
+use crate::imp::http_cache;
+use crate::imp::problem_info;
+use crate::imp::statement;
+use crate::imp::test_case::TestCaseFile;
+use anyhow::{anyhow, ensure};
+use anyhow::{Context, Result};
+use reqwest::Url;
+use scraper::{Html, Selector};
+
+/// A provider for any site, which extracts samples by CSS selectors in the
+/// config (`fetch.sites`).
+#[derive(Debug)]
+pub struct Generic {
+    url: String,
+    selectors: SiteSelectors,
+}
+
+impl Generic {
+    pub fn new(url: String) -> Result<Generic> {
+        let parsed = Url::parse(&url).with_context(|| format!("invalid url: {}", url))?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| anyhow!("url has no host: {}", url))?;
+        let selectors = find_selectors(host)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no selectors are configured for `{}` in `fetch.sites`",
+                    host
+                )
+            })?
+            .clone();
+
+        Ok(Generic { url, selectors })
+    }
+}
+
+/// Finds the selectors for the host. The entry for a parent domain is also
+/// used, e.g. `example.com` for `www.example.com`.
+fn find_selectors(host: &str) -> Option<&'static SiteSelectors> {
+    let sites = &CONFIG.fetch.sites;
+    let mut domain = host;
+    loop {
+        if let Some(selectors) = sites.get(domain) {
+            return Some(selectors);
+        }
+        domain = domain.split_once('.')?.1;
+    }
+}
+
+impl TestCaseProvider for Generic {
+    fn site_name(&self) -> &str {
+        "Generic"
+    }
+
+    fn problem_id(&self) -> &str {
+        "Unknown"
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn fetch_problem(&self) -> Result<FetchedProblem> {
+        let text = http_cache::download_text(&self.url)?;
+        let samples = parse_samples(&text, &self.selectors)?;
+        let statement = self
+            .selectors
+            .statement
+            .as_ref()
+            .and_then(|sel| statement::extract(&text, &[sel]));
+
+        let idx_start = TestCaseFile::next_unused_idx().context("failed to get unused index")?;
+        let test_cases = samples
+            .into_iter()
+            .zip(idx_start..)
+            .map(|((input, output), idx)| TestCaseFile::new_with_idx(idx, input, output))
+            .collect();
+
+        Ok(FetchedProblem {
+            test_cases,
+            statement,
+            info: problem_info::parse_page(
+                &text,
+                "title",
+                &[self.selectors.statement.as_deref().unwrap_or("body")],
+            ),
+        })
+    }
+}
+
+fn parse_samples(text: &str, selectors: &SiteSelectors) -> Result<Vec<(String, String)>> {
+    let parse_selector = |sel: &str| {
+        Selector::parse(sel).map_err(|e| anyhow!("invalid selector `{}`: {:?}", sel, e))
+    };
+    let sel_input = parse_selector(&selectors.input)?;
+    let sel_output = parse_selector(&selectors.output)?;
+
+    let document = Html::parse_document(text);
+    let select_all = |sel: &Selector| {
+        document
+            .select(sel)
+            .map(|elem| super::normalize_sample(&elem.text().collect::<String>()))
+            .collect::<Vec<_>>()
+    };
+    let inputs = select_all(&sel_input);
+    let outputs = select_all(&sel_output);
+    ensure!(
+        inputs.len() == outputs.len(),
+        "found {} inputs but {} outputs",
+        inputs.len(),
+        outputs.len()
+    );
+
+    Ok(inputs.into_iter().zip(outputs).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_samples() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/generic/problem.html"
+        ));
+        let selectors = SiteSelectors {
+            input: ".example-in pre".to_string(),
+            output: ".example-out pre".to_string(),
+            statement: None,
+        };
+        let samples = parse_samples(text, &selectors).unwrap();
+        assert_eq!(
+            samples,
+            vec![
+                ("1 2\n".to_string(), "3\n".to_string()),
+                ("-1 1\n".to_string(), "0\n".to_string()),
+            ]
+        );
+    }
+}
@@ -1,8 +1,17 @@
 pub mod aoj;
 pub mod atcoder;
+pub mod codeforces;
+pub mod file;
+pub mod generic;
+pub mod yukicoder;
 
 use self::aoj::Aoj;
 use self::atcoder::{AtCoder, Problem as AtCoderProblem};
+use self::codeforces::{Codeforces, Problem as CodeforcesProblem};
+use self::file::File;
+use self::generic::Generic;
+use self::yukicoder::{Problem as YukicoderProblem, Yukicoder};
+use crate::imp::problem_info::ProblemInfo;
 use crate::imp::test_case::TestCaseFile;
 use anyhow::{bail, ensure};
 use anyhow::{Context, Result};
@@ -24,6 +33,14 @@ use std::fmt::Debug;
 // atcoder:abc092a
 // ^^^^^^^^^^^^^^^ problem-descriptor
 
+// atcoder:typical90/a
+//         typical90
+//         ^^^^^^^^^   contest-id
+//                   a
+//                   ^ problem-name
+//         typical90/a
+//         ^^^^^^^^^^^ problem-id
+
 // aoj:0000
 // aoj
 // ^^^      contest-site
@@ -32,6 +49,48 @@ use std::fmt::Debug;
 // aoj:0000
 // ^^^^^^^^ problem-descriptor
 
+// aoj:ITP1_1_A
+//     ITP1_1_A
+//     ^^^^^^^^ problem-id
+// aoj:ITP1
+// ^^^^^^^^     contest-descriptor (contest-id is a course or a volume number)
+
+// codeforces:1234A
+// codeforces
+// ^^^^^^^^^^       contest-site
+//                A
+//                ^ problem-name
+//            1234
+//            ^^^^  contest-id
+//            1234A
+//            ^^^^^ problem-id
+// codeforces:1234
+// ^^^^^^^^^^^^^^^  contest-descriptor
+// codeforces:1234A
+// ^^^^^^^^^^^^^^^^ problem-descriptor
+
+// yukicoder:No.123
+// yukicoder
+// ^^^^^^^^^        contest-site
+//           No.123
+//           ^^^^^^ problem-id
+// yukicoder:No.123
+// ^^^^^^^^^^^^^^^^ problem-descriptor
+// yukicoder:300
+// ^^^^^^^^^^^^^    contest-descriptor (contest-id is the number of the contest)
+
+// file:/path/to/problem.html
+// file
+// ^^^^                      contest-site
+//      /path/to/problem.html
+//      ^^^^^^^^^^^^^^^^^^^^^ problem-id (`-` for the standard input)
+
+// url:https://example.com/problems/1
+// url
+// ^^^                                contest-site (`generic` is also accepted)
+//     https://example.com/problems/1
+//     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ problem-id (selectors are looked up by its domain)
+
 #[derive(Debug, Clone)]
 pub struct ProblemDescriptor {
     pub contest_site: String,
@@ -74,14 +133,58 @@ impl ProblemDescriptor {
                 };
                 Ok(Box::new(AtCoder::new(problem)))
             }
+            "codeforces" | "cf" => {
+                let problem = if self.problem_id.starts_with("http") {
+                    CodeforcesProblem::from_url(self.problem_id)
+                } else {
+                    CodeforcesProblem::from_problem_id(self.problem_id)
+                        .context("failed to create the provider Codeforces")?
+                };
+                Ok(Box::new(Codeforces::new(problem)))
+            }
+            "file" => File::new(self.problem_id, None)
+                .context("failed to create the provider File")
+                .map(|p| Box::new(p) as _),
+            "url" | "generic" => Generic::new(self.problem_id)
+                .context("failed to create the provider Generic")
+                .map(|p| Box::new(p) as _),
+            "yukicoder" | "yuki" => {
+                let problem = if self.problem_id.starts_with("http") {
+                    YukicoderProblem::from_url(self.problem_id)
+                } else {
+                    YukicoderProblem::from_problem_id(self.problem_id)
+                        .context("failed to create the provider Yukicoder")?
+                };
+                Ok(Box::new(Yukicoder::new(problem)))
+            }
             other => bail!("unknown contest site: {}", other),
         }
     }
 }
 
-pub trait TestCaseProvider: Debug {
+/// Normalizes the text of a sample taken from HTML: leading newlines (which
+/// often follow `<pre>`) are removed and the text ends with a newline.
+pub fn normalize_sample(text: &str) -> String {
+    let text = text.trim_start_matches(['\r', '\n']).trim_end();
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+/// What a provider got from the problem page.
+#[derive(Debug)]
+pub struct FetchedProblem {
+    pub test_cases: Vec<TestCaseFile>,
+    /// The problem statement as sanitized HTML, if the provider supports it.
+    pub statement: Option<String>,
+    pub info: ProblemInfo,
+}
+
+pub trait TestCaseProvider: Debug + Send {
     fn site_name(&self) -> &str;
     fn problem_id(&self) -> &str;
     fn url(&self) -> &str;
-    fn fetch_test_case_files(&self) -> Result<Vec<TestCaseFile>>;
+    fn fetch_problem(&self) -> Result<FetchedProblem>;
 }
@@ -0,0 +1,176 @@
+use super::{FetchedProblem, TestCaseProvider};
+use crate::imp::http_cache;
+use crate::imp::problem_info::{self, ProblemInfo};
+use crate::imp::statement;
+use crate::imp::test_case::TestCaseFile;
+use anyhow::anyhow;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{Html, Selector};
+
+pub const YUKICODER_TOP: &str = "https://yukicoder.me";
+
+lazy_static! {
+    static ref RE_PROBLEM_NO: Regex = Regex::new(r"^(?i:no\.?)?(?P<no>\d+)$").unwrap();
+}
+
+#[derive(Debug)]
+pub struct Yukicoder {
+    problem: Problem,
+}
+
+impl Yukicoder {
+    pub fn new(problem: Problem) -> Yukicoder {
+        Yukicoder { problem }
+    }
+}
+
+#[derive(Debug)]
+pub enum Problem {
+    ProblemNo { problem_id: String, url: String },
+    DirectUrl { url: String },
+}
+
+impl Problem {
+    /// Parses the problem number like `No.123` (or simply `123`).
+    pub fn from_problem_id(problem_id: String) -> Result<Problem> {
+        let caps = RE_PROBLEM_NO
+            .captures(&problem_id)
+            .ok_or_else(|| anyhow!("invalid format for problem id: {}", problem_id))?;
+        let url = format!("{}/problems/no/{}", YUKICODER_TOP, &caps["no"]);
+
+        Ok(Problem::ProblemNo { problem_id, url })
+    }
+
+    pub fn from_url(url: String) -> Problem {
+        Problem::DirectUrl { url }
+    }
+
+    pub fn problem_id(&self) -> &str {
+        match self {
+            Problem::ProblemNo { problem_id, .. } => problem_id,
+            Problem::DirectUrl { .. } => "Unknown",
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            Problem::ProblemNo { url, .. } => url,
+            Problem::DirectUrl { url } => url,
+        }
+    }
+}
+
+impl TestCaseProvider for Yukicoder {
+    fn site_name(&self) -> &str {
+        "yukicoder"
+    }
+
+    fn problem_id(&self) -> &str {
+        self.problem.problem_id()
+    }
+
+    fn url(&self) -> &str {
+        self.problem.url()
+    }
+
+    fn fetch_problem(&self) -> Result<FetchedProblem> {
+        let text = download_text(self.problem.url())?;
+        parse_problem(&text)
+    }
+}
+
+pub fn download_text(url: &str) -> Result<String> {
+    http_cache::download_text(url)
+}
+
+/// Parses everything on the problem page.
+pub fn parse_problem(text: &str) -> Result<FetchedProblem> {
+    Ok(FetchedProblem {
+        test_cases: parse_text(text)?,
+        statement: parse_statement(text),
+        info: parse_info(text),
+    })
+}
+
+pub fn parse_info(text: &str) -> ProblemInfo {
+    // the judge type is shown above the statement
+    problem_info::parse_page(text, "#content h3", &["#content"])
+}
+
+pub fn parse_statement(text: &str) -> Option<String> {
+    statement::extract(text, &["#problem_text"])
+}
+
+pub fn parse_text(text: &str) -> Result<Vec<TestCaseFile>> {
+    let idx_start = TestCaseFile::next_unused_idx().context("failed to get unused index")?;
+    Ok(parse_samples(text)
+        .into_iter()
+        .zip(idx_start..)
+        .map(|((input, output), idx)| TestCaseFile::new_with_idx(idx, input, output))
+        .collect())
+}
+
+/// Extracts pairs of the sample input and output. Each sample is in
+/// `<div class="sample">` which has the input and the output in `<pre>`.
+fn parse_samples(text: &str) -> Vec<(String, String)> {
+    let document = Html::parse_document(text);
+    let sel_sample = Selector::parse("div.sample").unwrap();
+    let sel_pre = Selector::parse("pre").unwrap();
+
+    document
+        .select(&sel_sample)
+        .filter_map(|sample| {
+            let (input, output) = sample
+                .select(&sel_pre)
+                .map(|pre| super::normalize_sample(&pre.text().collect::<String>()))
+                .next_tuple()?;
+            Some((input, output))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_samples() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/yukicoder/problem.html"
+        ));
+        let samples = parse_samples(text);
+        assert_eq!(
+            samples,
+            vec![
+                ("3 2\n3 2\n".to_string(), "2\n".to_string()),
+                ("5 3\n1 5 5\n".to_string(), "5\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/yukicoder/problem.html"
+        ));
+        let info = parse_info(text);
+        assert_eq!(info.title.as_deref(), Some("No.123 カードシャッフル"));
+        assert_eq!(info.time_limit_milliseconds, Some(5000));
+        assert_eq!(info.memory_limit_megabytes, Some(512));
+        assert!(!info.special_judge);
+    }
+
+    #[test]
+    fn test_problem_id() {
+        for id in ["No.123", "no123", "123"] {
+            let problem = Problem::from_problem_id(id.to_string()).unwrap();
+            assert_eq!(problem.url(), "https://yukicoder.me/problems/no/123");
+        }
+        assert!(Problem::from_problem_id("No.".to_string()).is_err());
+    }
+}
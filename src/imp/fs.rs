@@ -7,6 +7,10 @@ use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 
+/// The directory in each problem directory where procon-assistant keeps its own
+/// data, such as the removed test cases.
+pub const DATA_DIR: &str = ".procon-assistant";
+
 /// Infers workspace root and sets the current directory to it.
 pub fn adjust_workspace_root() {
     let orig = env::current_dir().expect("critical error: failed to get current dir");
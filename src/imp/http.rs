@@ -0,0 +1,345 @@
+//! The HTTP client shared by all providers. The settings (user agent,
+//! timeouts and proxy) are taken from `http` in the config, and each site
+//! with login keeps its cookies in a [`Session`]. Requests sent through
+//! [`send_with_retry`] keep an interval per host and are retried on transient
+//! failures.
+
+use crate::imp::auth;
+use crate::imp::config::CONFIG;
+use crate::{eprintln_debug, eprintln_warning};
+use anyhow::{Context, Result};
+use cookie_store::{CookieStore, RawCookie};
+use lazy_static::lazy_static;
+use reqwest::blocking::{Client, ClientBuilder, Response};
+use reqwest::{Proxy, StatusCode, Url};
+use reqwest_cookie_store::CookieStoreMutex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+pub const MAX_RETRIES: u32 = 4;
+
+lazy_static! {
+    static ref CLIENT: Mutex<Option<Client>> = Mutex::new(None);
+    static ref SESSIONS: Mutex<HashMap<String, Arc<Session>>> = Mutex::new(HashMap::new());
+    static ref RATE_LIMITER: RateLimiter = RateLimiter::new(MIN_REQUEST_INTERVAL);
+}
+
+fn builder() -> Result<ClientBuilder> {
+    let config = &CONFIG.http;
+    let mut builder = ClientBuilder::new()
+        .user_agent(&config.user_agent)
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .connect_timeout(Duration::from_secs(config.connect_timeout_seconds));
+    if let Some(proxy) = &config.proxy {
+        let proxy = Proxy::all(proxy).with_context(|| format!("invalid proxy: `{}`", proxy))?;
+        builder = builder.proxy(proxy);
+    }
+
+    Ok(builder)
+}
+
+/// The client for the requests without login.
+pub fn client() -> Result<Client> {
+    let mut client = CLIENT.lock().unwrap();
+    if let Some(client) = &*client {
+        return Ok(client.clone());
+    }
+
+    let built = builder()?
+        .build()
+        .context("failed to initialize the client")?;
+    *client = Some(built.clone());
+
+    Ok(built)
+}
+
+/// Sends a request to `url` with `send`, waiting for the interval since the
+/// last request to the same host. Transient failures are retried up to
+/// `max_retries` times with exponential backoff; the response of the last try
+/// is returned even if its status is an error.
+pub fn send_with_retry(
+    url: &str,
+    max_retries: u32,
+    mut send: impl FnMut() -> Result<Response>,
+) -> Result<Response> {
+    let host = Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(ToString::to_string))
+        .unwrap_or_default();
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut retries = 0;
+    loop {
+        RATE_LIMITER.wait(&host);
+        let error = match send() {
+            Ok(res) if retries == max_retries || !is_transient_status(res.status()) => {
+                return Ok(res)
+            }
+            Ok(res) => format!("the server returned {}", res.status()),
+            Err(e) if retries == max_retries || !is_transient(&e) => return Err(e),
+            Err(e) => format!("{:#}", e),
+        };
+
+        eprintln_warning!(
+            "failed to get {}; retrying in {} secs: {}",
+            url,
+            backoff.as_secs(),
+            error
+        );
+        thread::sleep(backoff);
+        backoff *= 2;
+        retries += 1;
+    }
+}
+
+/// Whether the error is worth retrying: timeouts, connection errors, too many
+/// requests and server errors.
+pub fn is_transient(e: &anyhow::Error) -> bool {
+    e.chain()
+        .filter_map(|e| e.downcast_ref::<reqwest::Error>())
+        .any(|e| e.is_timeout() || e.is_connect() || e.status().is_some_and(is_transient_status))
+}
+
+pub fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Keeps an interval between requests to the same host.
+struct RateLimiter {
+    interval: Duration,
+    next_slots: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    fn new(interval: Duration) -> RateLimiter {
+        RateLimiter {
+            interval,
+            next_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn wait(&self, host: &str) {
+        let slot = {
+            let mut next_slots = self.next_slots.lock().unwrap();
+            let now = Instant::now();
+            let slot = next_slots.get(host).map_or(now, |&next| next.max(now));
+            next_slots.insert(host.to_string(), slot + self.interval);
+            slot
+        };
+
+        thread::sleep(slot.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// The cookies of a site kept in the session storage, and the client which
+/// sends them.
+pub struct Session {
+    service_name: String,
+    jar: Arc<CookieStoreMutex>,
+    client: Client,
+    /// The cookies as last loaded from or written to the session storage;
+    /// `None` while no session is stored.
+    stored: Mutex<Option<Vec<u8>>>,
+}
+
+impl Session {
+    /// Gets the session of the service, loading it on the first use. `origin`
+    /// is the site the cookies saved by older versions belong to.
+    pub fn get(service_name: &str, origin: &str) -> Result<Arc<Session>> {
+        let mut sessions = SESSIONS.lock().unwrap();
+        if let Some(session) = sessions.get(service_name) {
+            return Ok(Arc::clone(session));
+        }
+
+        let session = if auth::has_session_info(service_name) {
+            let saved = auth::load_session_info(service_name)?;
+            let session = Session::with_store(service_name, load_cookies(&saved, origin)?)?;
+            *session.stored.lock().unwrap() = Some(session.serialize()?);
+            session
+        } else {
+            Session::with_store(service_name, CookieStore::default())?
+        };
+
+        let session = Arc::new(session);
+        sessions.insert(service_name.to_string(), Arc::clone(&session));
+
+        Ok(session)
+    }
+
+    fn with_store(service_name: &str, store: CookieStore) -> Result<Session> {
+        let jar = Arc::new(CookieStoreMutex::new(store));
+        let client = builder()?
+            .cookie_provider(Arc::clone(&jar))
+            .build()
+            .context("failed to initialize the client")?;
+
+        Ok(Session {
+            service_name: service_name.to_string(),
+            jar,
+            client,
+            stored: Mutex::new(None),
+        })
+    }
+
+    /// A session which is not loaded from the session storage, for tests.
+    #[cfg(test)]
+    pub fn empty(service_name: &str) -> Session {
+        Session::with_store(service_name, CookieStore::default()).unwrap()
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Forgets all cookies, e.g. before logging in again.
+    pub fn clear(&self) {
+        self.jar.lock().unwrap().clear();
+    }
+
+    pub fn insert_cookie(&self, url: &str, name: &str, value: &str) -> Result<()> {
+        let url = Url::parse(url).with_context(|| format!("invalid url: `{}`", url))?;
+        let cookie = RawCookie::build((name.to_string(), value.to_string()))
+            .path("/")
+            .build();
+        self.jar
+            .lock()
+            .unwrap()
+            .insert_raw(&cookie, &url)
+            .with_context(|| format!("failed to set the cookie `{}`", name))?;
+
+        Ok(())
+    }
+
+    /// Writes the cookies to the session storage, e.g. after logging in.
+    pub fn save(&self) -> Result<()> {
+        let mut stored = self.stored.lock().unwrap();
+        let cookies = self.serialize()?;
+        auth::store_session_info(&self.service_name, &cookies)?;
+        *stored = Some(cookies);
+
+        Ok(())
+    }
+
+    /// Writes the cookies back to the session storage if the server has
+    /// changed them, e.g. renewed the session cookie. Nothing is written
+    /// unless a session is already stored, so browsing anonymously does not
+    /// create one.
+    pub fn save_if_changed(&self) -> Result<()> {
+        let mut stored = self.stored.lock().unwrap();
+        if let Some(cookies) = self.changes(&stored)? {
+            auth::store_session_info(&self.service_name, &cookies)?;
+            *stored = Some(cookies);
+        }
+
+        Ok(())
+    }
+
+    fn changes(&self, stored: &Option<Vec<u8>>) -> Result<Option<Vec<u8>>> {
+        let Some(stored) = stored else {
+            return Ok(None);
+        };
+        let cookies = self.serialize()?;
+
+        Ok((cookies != *stored).then_some(cookies))
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>> {
+        let store = self.jar.lock().unwrap();
+        let mut cookies = Vec::new();
+        cookie_store::serde::json::save_incl_expired_and_nonpersistent(&store, &mut cookies)
+            .map_err(|e| anyhow::anyhow!("{}", e))
+            .context("failed to serialize the cookies")?;
+
+        Ok(cookies)
+    }
+}
+
+fn load_cookies(saved: &[u8], origin: &str) -> Result<CookieStore> {
+    if let Ok(store) = cookie_store::serde::json::load(saved) {
+        return Ok(store);
+    }
+
+    // older versions saved `name\tvalue` per line
+    eprintln_debug!("loading the cookies in the old format");
+    let url = Url::parse(origin).with_context(|| format!("invalid url: `{}`", origin))?;
+    let mut store = CookieStore::default();
+    for (name, value) in parse_legacy_cookies(&String::from_utf8_lossy(saved)) {
+        let cookie = RawCookie::build((name, value)).path("/").build();
+        if let Err(e) = store.insert_raw(&cookie, &url) {
+            eprintln_debug!("skipping a cookie: {}", e);
+        }
+    }
+
+    Ok(store)
+}
+
+/// Parses the cookies saved by older versions, one `name\tvalue` per line.
+/// Broken lines are skipped.
+fn parse_legacy_cookies(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let cookie = line.split_once('\t');
+            if cookie.is_none() {
+                eprintln_debug!("skipping broken cookie line: {}", line);
+            }
+            cookie
+        })
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_cookies() {
+        let origin = "https://atcoder.jp/";
+        let store =
+            load_cookies(b"REVEL_SESSION\tabc=def\nbroken\n\nREVEL_FLASH\t\n", origin).unwrap();
+        let url = Url::parse("https://atcoder.jp/contests/abc300/tasks").unwrap();
+        let mut cookies: Vec<_> = store.get_request_values(&url).collect();
+        cookies.sort();
+        assert_eq!(
+            cookies,
+            vec![("REVEL_FLASH", ""), ("REVEL_SESSION", "abc=def")]
+        );
+
+        // saved in the current format
+        let mut saved = Vec::new();
+        cookie_store::serde::json::save_incl_expired_and_nonpersistent(&store, &mut saved).unwrap();
+        let store = load_cookies(&saved, origin).unwrap();
+        assert_eq!(store.get_request_values(&url).count(), 2);
+    }
+
+    #[test]
+    fn test_changes() {
+        let session = Session::empty("test");
+        let origin = "https://atcoder.jp/";
+
+        // not stored
+        session
+            .insert_cookie(origin, "REVEL_SESSION", "abc")
+            .unwrap();
+        assert_eq!(session.changes(&None).unwrap(), None);
+
+        let stored = Some(session.serialize().unwrap());
+        assert_eq!(session.changes(&stored).unwrap(), None);
+
+        session
+            .insert_cookie(origin, "REVEL_SESSION", "def")
+            .unwrap();
+        let cookies = session.changes(&stored).unwrap().unwrap();
+        let store = load_cookies(&cookies, origin).unwrap();
+        let url = Url::parse(origin).unwrap();
+        assert_eq!(
+            store.get_request_values(&url).collect::<Vec<_>>(),
+            vec![("REVEL_SESSION", "def")]
+        );
+    }
+}
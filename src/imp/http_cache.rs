@@ -0,0 +1,276 @@
+//! On-disk cache of fetched pages keyed by URL. Cached pages are revalidated
+//! with `ETag` / `Last-Modified`, and used as they are when the site is not
+//! reachable.
+
+use crate::imp::{auth, http};
+use crate::{eprintln_debug, eprintln_warning};
+use anyhow::anyhow;
+use anyhow::{Context, Result};
+use reqwest::blocking::Response;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REFRESH: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Ignores the cached pages and fetches everything again.
+pub fn set_refresh(refresh: bool) {
+    REFRESH.store(refresh, Ordering::SeqCst);
+}
+
+/// Gets the page without login.
+pub fn download_text(url: &str) -> Result<String> {
+    get_text(url, |headers| {
+        http::client()?
+            .get(url)
+            .headers(headers)
+            .send()
+            .map_err(Into::into)
+    })
+}
+
+/// Gets the page through the cache. `send` requests the page with the given
+/// headers added, and is called again to retry.
+pub fn get_text(url: &str, send: impl Fn(HeaderMap) -> Result<Response>) -> Result<String> {
+    let refresh = REFRESH.load(Ordering::SeqCst);
+    get_text_in(cache_dir().as_deref(), refresh, url, send)
+}
+
+fn get_text_in(
+    dir: Option<&Path>,
+    refresh: bool,
+    url: &str,
+    send: impl Fn(HeaderMap) -> Result<Response>,
+) -> Result<String> {
+    let cached = if refresh {
+        None
+    } else {
+        dir.and_then(|dir| load(dir, url))
+    };
+
+    let mut headers = HeaderMap::new();
+    if let Some(cached) = &cached {
+        let validators = [
+            (header::IF_NONE_MATCH, &cached.etag),
+            (header::IF_MODIFIED_SINCE, &cached.last_modified),
+        ];
+        for (name, value) in validators {
+            if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    // the cached page is used instead of waiting for the site to recover
+    let max_retries = if cached.is_some() {
+        0
+    } else {
+        http::MAX_RETRIES
+    };
+    let res = match http::send_with_retry(url, max_retries, || send(headers.clone())) {
+        Ok(res) if cached.is_some() && http::is_transient_status(res.status()) => {
+            Err(anyhow!("the server returned {}", res.status()))
+        }
+        res => res,
+    };
+    let res = match res {
+        Ok(res) => res,
+        Err(e) => match cached {
+            Some(cached) => {
+                eprintln_warning!("failed to get `{}`; using the cached page: {:#}", url, e);
+                return Ok(cached.body);
+            }
+            None => return Err(e).with_context(|| format!("failed to get `{}`", url)),
+        },
+    };
+
+    if res.status() == StatusCode::NOT_MODIFIED {
+        // not asked to revalidate anything; the server is broken
+        let cached = cached.ok_or_else(|| {
+            anyhow!(
+                "failed to get `{}`: not modified, but nothing is cached",
+                url
+            )
+        })?;
+        eprintln_debug!("`{}` is not modified; using the cached page", url);
+        return Ok(cached.body);
+    }
+
+    let res = res
+        .error_for_status()
+        .with_context(|| format!("failed to get `{}`", url))?;
+    let header_value = |name| {
+        res.headers()
+            .get(name)
+            .and_then(|v: &HeaderValue| v.to_str().ok())
+            .map(ToString::to_string)
+    };
+    let etag = header_value(header::ETAG);
+    let last_modified = header_value(header::LAST_MODIFIED);
+    let body = res.text().context("failed to get the text")?;
+
+    let entry = Entry {
+        url: url.to_string(),
+        etag,
+        last_modified,
+        body,
+    };
+    if let Some(dir) = dir {
+        if let Err(e) = store(dir, &entry) {
+            eprintln_debug!("failed to cache `{}`: {:#}", url, e);
+        }
+    }
+
+    Ok(entry.body)
+}
+
+fn load(dir: &Path, url: &str) -> Option<Entry> {
+    let text = fs::read_to_string(entry_path(dir, url)).ok()?;
+    let entry: Entry = serde_json::from_str(&text).ok()?;
+
+    // the file name is a hash; make sure it is not a collision.
+    (entry.url == url).then_some(entry)
+}
+
+/// Pages may be seen only with login, so the cache is kept private.
+fn store(dir: &Path, entry: &Entry) -> Result<()> {
+    auth::create_private_dir(dir)
+        .with_context(|| format!("failed to create `{}`", dir.display()))?;
+    let text = serde_json::to_string(entry).context("failed to serialize")?;
+    auth::write_private(&entry_path(dir, &entry.url), text.as_bytes())
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("procon-assistant").join("http"))
+}
+
+fn entry_path(dir: &Path, url: &str) -> PathBuf {
+    dir.join(format!("{:016x}.json", fnv1a(url.as_bytes())))
+}
+
+/// A hash stable across builds, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use tempdir::TempDir;
+
+    const URL: &str = "https://example.com/problem";
+
+    fn response(status: u16, etag: Option<&str>, body: &'static str) -> Result<Response> {
+        let mut builder = ::http::Response::builder().status(status);
+        if let Some(etag) = etag {
+            builder = builder.header(header::ETAG, etag);
+        }
+        Ok(Response::from(builder.body(body).unwrap()))
+    }
+
+    #[test]
+    fn test_revalidate() {
+        let dir = TempDir::new("procon-assistant-test").unwrap();
+        let dir = Some(dir.path());
+        let sent = RefCell::new(Vec::new());
+        let send = |status, body| {
+            let sent = &sent;
+            move |headers: HeaderMap| {
+                sent.borrow_mut()
+                    .push(headers.get(header::IF_NONE_MATCH).cloned());
+                response(status, Some("\"v1\""), body)
+            }
+        };
+
+        assert_eq!(get_text_in(dir, false, URL, send(200, "v1")).unwrap(), "v1");
+        assert_eq!(get_text_in(dir, false, URL, send(304, "")).unwrap(), "v1");
+        assert_eq!(
+            sent.take(),
+            [None, Some(HeaderValue::from_static("\"v1\""))]
+        );
+
+        // offline
+        let text = get_text_in(dir, false, URL, |_| Err(anyhow!("offline"))).unwrap();
+        assert_eq!(text, "v1");
+
+        // `--refresh` does not revalidate, and updates the cache
+        assert_eq!(get_text_in(dir, true, URL, send(200, "v2")).unwrap(), "v2");
+        assert_eq!(sent.take(), [None]);
+        let text = get_text_in(dir, false, URL, |_| Err(anyhow!("offline"))).unwrap();
+        assert_eq!(text, "v2");
+    }
+
+    #[test]
+    fn test_server_error() {
+        const URL: &str = "https://example.com/busy";
+        let dir = TempDir::new("procon-assistant-test").unwrap();
+        let dir = Some(dir.path());
+
+        get_text_in(dir, false, URL, |_| response(200, None, "v1")).unwrap();
+        let sent = RefCell::new(0);
+        let text = get_text_in(dir, false, URL, |_| {
+            *sent.borrow_mut() += 1;
+            response(503, None, "busy")
+        })
+        .unwrap();
+        assert_eq!(text, "v1");
+        assert_eq!(sent.take(), 1);
+
+        // not a transient error
+        assert!(get_text_in(dir, false, URL, |_| response(404, None, "")).is_err());
+    }
+
+    #[test]
+    fn test_not_cached() {
+        // requests to the same host are kept apart; use another one
+        const URL: &str = "https://example.org/problem";
+        let dir = TempDir::new("procon-assistant-test").unwrap();
+        let path = dir.path();
+        let dir = Some(path);
+
+        assert!(get_text_in(dir, false, URL, |_| response(304, None, "")).is_err());
+        assert!(get_text_in(dir, false, URL, |_| response(404, None, "")).is_err());
+
+        // a page of another URL with the same hash is not used
+        let entry = Entry {
+            url: "https://example.com/other".to_string(),
+            etag: None,
+            last_modified: None,
+            body: "other".to_string(),
+        };
+        fs::write(
+            entry_path(path, URL),
+            serde_json::to_string(&entry).unwrap(),
+        )
+        .unwrap();
+        assert!(get_text_in(dir, false, URL, |_| Err(anyhow!("offline"))).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        const URL: &str = "https://example.net/problem";
+        let dir = TempDir::new("procon-assistant-test").unwrap();
+        let cache = dir.path().join("http");
+        get_text_in(Some(&cache), false, URL, |_| response(200, None, "v1")).unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&cache), 0o700);
+        assert_eq!(mode(&entry_path(&cache, URL)), 0o600);
+    }
+}
@@ -63,7 +63,8 @@ impl Lang for Cpp {
             .map_err(Into::into)
     }
 
-    fn init_async(&self) -> Progress<anyhow::Result<()>> {
+    fn init_async(&self, dir: &Path, _shared_cache: Option<&Path>) -> Progress<anyhow::Result<()>> {
+        let dir = dir.to_path_buf();
         Progress::from_fn(move |sender| {
             let template_dir = &CONFIG.langs.cpp.template_dir;
 
@@ -90,7 +91,7 @@ impl Lang for Cpp {
                 })?;
 
                 let _ = sender.send(format!("generating `{}`", path.display()));
-                safe_generate(path)?;
+                safe_generate(&dir, path)?;
             }
 
             Ok(())
@@ -232,20 +233,20 @@ fn libdir() -> PathBuf {
     home_dir
 }
 
-fn safe_generate(path: &Path) -> Result<()> {
-    if path.exists() {
+fn safe_generate(dir: &Path, path: &Path) -> Result<()> {
+    if dir.join(path).exists() {
         eprintln_debug!("file {} already exists, skipping", path.display());
         return Ok(());
     }
 
-    generate(path)?;
+    generate(dir, path)?;
 
     Ok(())
 }
 
-fn generate(path: &Path) -> Result<()> {
+fn generate(dir: &Path, path: &Path) -> Result<()> {
     let path_template = CONFIG.langs.cpp.template_dir.join(path);
-    let path_project = path;
+    let path_project = dir.join(path);
 
     eprintln_debug!("loading template from `{}`", path_template.display());
 
@@ -256,14 +257,14 @@ fn generate(path: &Path) -> Result<()> {
         )
     })?;
 
-    let abs_path_project_root = to_absolute::to_absolute_from_current_dir(".")
+    let abs_path_project_root = to_absolute::to_absolute_from_current_dir(dir)
         .with_context(|| "get absolute path for current directory".to_string())?;
     let template = template
         .replace("$LIB_DIR", &libdir_escaped())
         .replace("$GDB_PATH", &gdbpath_escaped())
         .replace("$PROJECT_PATH", &escape_path(abs_path_project_root));
 
-    write_file_ensure_parent_dirs(path_project, &template)
+    write_file_ensure_parent_dirs(&path_project, &template)
 }
 
 fn write_file_ensure_parent_dirs(path: &Path, contents: &str) -> Result<()> {
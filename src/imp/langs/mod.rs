@@ -12,7 +12,7 @@ use anyhow::{Context, Result};
 use indexmap::indexmap;
 use indexmap::IndexMap;
 use lazy_static::lazy_static;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 pub struct RawSource(pub String);
@@ -38,7 +38,9 @@ pub trait Lang {
 
     fn get_lang_name(&self) -> &'static str;
 
-    fn init_async(&self) -> Progress<anyhow::Result<()>>;
+    /// Initializes the project in `dir`. Projects initialized together may
+    /// share `shared_cache` as their build cache.
+    fn init_async(&self, dir: &Path, shared_cache: Option<&Path>) -> Progress<anyhow::Result<()>>;
     fn to_open(&self) -> Result<FilesToOpen>;
     fn open_docs(&self) -> Result<()>;
     fn needs_compile(&self) -> Result<bool>;
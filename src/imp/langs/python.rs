@@ -32,18 +32,19 @@ impl Lang for Python {
             .map_err(Into::into)
     }
 
-    fn init_async(&self) -> Progress<anyhow::Result<()>> {
+    fn init_async(&self, dir: &Path, _shared_cache: Option<&Path>) -> Progress<anyhow::Result<()>> {
+        let dir = dir.to_path_buf();
         Progress::from_fn(move |sender| {
             let _ = sender.send("creating main.py".into());
-            let path_main = Path::new("main.py");
+            let path_main = dir.join("main.py");
             if !path_main.exists() {
                 stdfs::write(path_main, "").context("failed to create main.py")?;
             }
 
             let _ = sender.send("generating Visual Studio Code settings".into());
-            stdfs::create_dir_all(Path::new(".vscode")).context("failed to create .vscode dir")?;
+            stdfs::create_dir_all(dir.join(".vscode")).context("failed to create .vscode dir")?;
             stdfs::write(
-                Path::new(".vscode").join("settings.json"),
+                dir.join(".vscode").join("settings.json"),
                 r#"{ "isProconProject": true }"#,
             )
             .context("failed to create Visual Studio Code settings")?;
@@ -70,8 +70,8 @@ impl Lang for RustAtCoder2020 {
         "Rust (AtCoder, 2020)"
     }
 
-    fn init_async(&self) -> Progress<Result<()>> {
-        init_async(JudgeEnvironment::AtCoder2020)
+    fn init_async(&self, dir: &Path, shared_cache: Option<&Path>) -> Progress<Result<()>> {
+        init_async(JudgeEnvironment::AtCoder2020, dir, shared_cache)
     }
 
     fn to_open(&self) -> Result<FilesToOpen> {
@@ -134,7 +134,13 @@ fn check(ver: JudgeEnvironment) -> Result<bool> {
     Ok(ver == guessed_ver)
 }
 
-fn init_async(ver: JudgeEnvironment) -> Progress<Result<()>> {
+fn init_async(
+    ver: JudgeEnvironment,
+    dir: &Path,
+    shared_cache: Option<&Path>,
+) -> Progress<Result<()>> {
+    let dir = dir.to_path_buf();
+    let shared_cache = shared_cache.map(Path::to_path_buf);
     Progress::from_fn(move |sender| {
         let _ = sender.send("generating new cargo project".into());
         // generate a project
@@ -142,14 +148,18 @@ fn init_async(ver: JudgeEnvironment) -> Progress<Result<()>> {
             JudgeEnvironment::AtCoder2020 => {
                 match &CONFIG.langs.rust_atc_2020.project_template {
                     RustProjectTemplate::Git { repository, branch } => {
-                        generate_git(repository, branch)
+                        generate_git(&dir, repository, branch)
                     }
-                    RustProjectTemplate::Local { path } => generate_local(path),
+                    RustProjectTemplate::Local { path } => generate_local(&dir, path),
                 }
                 .context("failed to generate a project")?;
             }
         }
 
+        if let Some(shared_cache) = &shared_cache {
+            link_target(&dir, shared_cache).context("failed to share the build cache")?;
+        }
+
         let _ = sender.send("building generated project".into());
         // pre-build the project
         let output = Command::new("cargo")
@@ -158,7 +168,7 @@ fn init_async(ver: JudgeEnvironment) -> Progress<Result<()>> {
             .stdin(Stdio::null())
             .stdout(Stdio::null())
             .stderr(Stdio::piped())
-            .current_dir("main")
+            .current_dir(dir.join("main"))
             .spawn()?
             .wait_with_output()?;
         ensure!(
@@ -276,8 +286,8 @@ fn lint(ver: JudgeEnvironment, source: &RawSource) -> Result<Vec<String>> {
     Ok(res)
 }
 
-fn generate_git(repository: &str, branch: &str) -> Result<()> {
-    if Path::new("main").exists() {
+fn generate_git(dir: &Path, repository: &str, branch: &str) -> Result<()> {
+    if dir.join("main").exists() {
         // skip generating everything if main directory exists
         return Ok(());
     }
@@ -297,6 +307,7 @@ fn generate_git(repository: &str, branch: &str) -> Result<()> {
         .arg(branch)
         .arg("--name")
         .arg("main")
+        .current_dir(dir)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
@@ -312,7 +323,7 @@ fn generate_git(repository: &str, branch: &str) -> Result<()> {
     Ok(())
 }
 
-fn generate_local(path: &Path) -> Result<()> {
+fn generate_local(dir: &Path, path: &Path) -> Result<()> {
     // split path components and recollect them to normalize path separators
     let path: PathBuf = path.components().collect();
     let path: &Path = &path;
@@ -324,7 +335,8 @@ fn generate_local(path: &Path) -> Result<()> {
         ..CopyOptions::new()
     };
 
-    let base_path = Path::new("main");
+    let base_path = dir.join("main");
+    let base_path: &Path = &base_path;
     stdfs::create_dir_all(base_path)?;
     for entry in stdfs::read_dir(path)? {
         let entry = entry?;
@@ -356,6 +368,21 @@ fn generate_local(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Symlinks the `target` directory of the project to `shared_cache` unless
+/// the project has one already (the local template links its own).
+fn link_target(dir: &Path, shared_cache: &Path) -> Result<()> {
+    let project_target_path = dir.join("main").join("target");
+    if project_target_path.symlink_metadata().is_ok() {
+        return Ok(());
+    }
+
+    stdfs::create_dir_all(shared_cache)?;
+    let shared_cache = stdfs::canonicalize(shared_cache)?;
+    symlink::symlink_dir(shared_cache, project_target_path)?;
+
+    Ok(())
+}
+
 fn expand_source(
     _ver: JudgeEnvironment,
     cwd: &Path,
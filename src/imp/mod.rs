@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod case_info;
 pub mod clip;
 pub mod compile;
 pub mod config;
@@ -6,8 +7,12 @@ pub mod debug_macros;
 pub mod download;
 pub mod fetch;
 pub mod fs;
+pub mod http;
+pub mod http_cache;
 pub mod initdirs;
 pub mod langs;
+pub mod problem_info;
 pub mod process;
 pub mod progress;
+pub mod statement;
 pub mod test_case;
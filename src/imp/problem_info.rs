@@ -0,0 +1,197 @@
+use crate::imp::fs::DATA_DIR;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PROBLEM_INFO_FILE: &str = "problem.json";
+
+const INTERACTIVE_KEYWORDS: &[&str] = &["interactive", "インタラクティブ", "リアクティブ"];
+
+/// Phrases which mean that several outputs are accepted.
+const SPECIAL_JUDGE_KEYWORDS: &[&str] = &[
+    "special judge",
+    "print any of them",
+    "output any of them",
+    "スペシャルジャッジ",
+    "どれを出力しても",
+    "いずれを出力しても",
+];
+
+lazy_static! {
+    static ref RE_TIME_LIMIT: Regex = Regex::new(
+        r"(?i)(?:time limit|時間制限)\D{0,20}?(?P<value>\d+(?:\.\d+)?)\s*(?P<unit>ms|msec|milliseconds?|sec|seconds?|秒)"
+    )
+    .unwrap();
+    static ref RE_MEMORY_LIMIT: Regex = Regex::new(
+        r"(?i)(?:memory limit|メモリ制限)\D{0,20}?(?P<value>\d+(?:\.\d+)?)\s*(?P<unit>kib|kb|kilobytes?|mib|mb|megabytes?|gib|gb|gigabytes?)"
+    )
+    .unwrap();
+}
+
+/// Information about the problem taken from the problem page.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ProblemInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_milliseconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_megabytes: Option<u64>,
+    #[serde(default)]
+    pub interactive: bool,
+    #[serde(default)]
+    pub special_judge: bool,
+}
+
+impl ProblemInfo {
+    /// Loads the information of the problem in the current directory, if saved.
+    pub fn load() -> Result<Option<ProblemInfo>> {
+        let path = default_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        serde_json::from_str(&text)
+            .map(Some)
+            .with_context(|| format!("failed to parse `{}`", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = default_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create `{}`", parent.display()))?;
+        }
+
+        let text = serde_json::to_string_pretty(self).context("failed to serialize")?;
+        fs::write(&path, text).with_context(|| format!("failed to write `{}`", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == ProblemInfo::default()
+    }
+
+    /// Sets the limits written like `Time Limit: 2 sec / Memory Limit: 1024 MB`
+    /// in the text. The limits not found are left as is.
+    pub fn set_limits_from(&mut self, text: &str) {
+        if let Some(time_limit) = parse_time_limit(text) {
+            self.time_limit_milliseconds = Some(time_limit);
+        }
+        if let Some(memory_limit) = parse_memory_limit(text) {
+            self.memory_limit_megabytes = Some(memory_limit);
+        }
+    }
+}
+
+/// Guesses the information from the problem page. The title is the first text
+/// in the element matched by `title_selector`, and the limits are looked for in
+/// the whole page. Whether the problem is interactive or has a special judge
+/// is looked for only in the statement, the element matched first by
+/// `statement_selectors`, so that navigation or footers don't affect them.
+pub fn parse_page(text: &str, title_selector: &str, statement_selectors: &[&str]) -> ProblemInfo {
+    let document = Html::parse_document(text);
+    let title = Selector::parse(title_selector)
+        .ok()
+        .and_then(|sel| document.select(&sel).next())
+        .and_then(|elem| elem.text().map(str::trim).find(|t| !t.is_empty()))
+        .map(ToString::to_string);
+
+    let statement = statement_selectors
+        .iter()
+        .find_map(|sel| document.select(&Selector::parse(sel).ok()?).next())
+        .map(|elem| elem.text().collect::<String>().to_lowercase())
+        .unwrap_or_default();
+    let mut info = ProblemInfo {
+        title,
+        interactive: INTERACTIVE_KEYWORDS.iter().any(|k| statement.contains(k)),
+        special_judge: SPECIAL_JUDGE_KEYWORDS.iter().any(|k| statement.contains(k)),
+        ..ProblemInfo::default()
+    };
+    info.set_limits_from(&document.root_element().text().collect::<String>());
+
+    info
+}
+
+fn parse_time_limit(text: &str) -> Option<u64> {
+    let caps = RE_TIME_LIMIT.captures(text)?;
+    let value: f64 = caps["value"].parse().ok()?;
+    let scale = if caps["unit"].to_ascii_lowercase().starts_with('m') {
+        1.0
+    } else {
+        1000.0
+    };
+
+    Some((value * scale).round() as u64)
+}
+
+fn parse_memory_limit(text: &str) -> Option<u64> {
+    let caps = RE_MEMORY_LIMIT.captures(text)?;
+    let value: f64 = caps["value"].parse().ok()?;
+    let scale = match caps["unit"].to_ascii_lowercase().chars().next()? {
+        'k' => 1.0 / 1024.0,
+        'g' => 1024.0,
+        _ => 1.0,
+    };
+
+    Some((value * scale).round() as u64)
+}
+
+fn default_path() -> PathBuf {
+    Path::new(DATA_DIR).join(PROBLEM_INFO_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_limits() {
+        let limits = |text: &str| (parse_time_limit(text), parse_memory_limit(text));
+        assert_eq!(
+            limits("Time Limit: 2 sec / Memory Limit: 1024 MB"),
+            (Some(2000), Some(1024))
+        );
+        assert_eq!(
+            limits("実行時間制限: 4.5 sec / メモリ制限: 1024 MiB"),
+            (Some(4500), Some(1024))
+        );
+        assert_eq!(
+            limits("time limit per test2 seconds memory limit per test256 megabytes"),
+            (Some(2000), Some(256))
+        );
+        assert_eq!(
+            limits("Time Limit : 500 ms , Memory Limit : 131072 KB"),
+            (Some(500), Some(128))
+        );
+        assert_eq!(
+            limits("時間制限 : 5 秒 / メモリ制限 : 512 MB"),
+            (Some(5000), Some(512))
+        );
+        assert_eq!(limits("print 2 sec"), (None, None));
+    }
+
+    #[test]
+    fn test_parse_page() {
+        let text = r#"<html><body>
+<nav><a href="/interactive">Interactive problems</a></nav>
+<h2>A - Guess</h2><p>Time Limit: 2 sec / Memory Limit: 1024 MB</p>
+<div id="statement"><p>Print any of them.</p></div>
+<footer>Special Judge</footer>
+</body></html>"#;
+        let info = parse_page(text, "h2", &["#none", "#statement"]);
+        assert_eq!(info.title.as_deref(), Some("A - Guess"));
+        assert_eq!(info.time_limit_milliseconds, Some(2000));
+        assert_eq!(info.memory_limit_megabytes, Some(1024));
+        assert!(!info.interactive);
+        assert!(info.special_judge);
+
+        let info = parse_page(text, "h2", &["#none"]);
+        assert!(!info.interactive && !info.special_judge);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::imp::fs::DATA_DIR;
+use anyhow::{Context, Result};
+use scraper::{ElementRef, Html, Node, Selector};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STATEMENT_FILE: &str = "statement.html";
+
+/// Elements dropped from the statement with their contents.
+const DROPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "form", "input", "button", "noscript",
+];
+
+/// Classes of the copy buttons placed by the sites; dropped as well.
+const DROPPED_CLASSES: &[&str] = &["btn-copy", "input-output-copier"];
+
+/// Attributes kept in the statement; the others (especially event handlers)
+/// are dropped.
+const KEPT_ATTRIBUTES: &[&str] = &["class", "href", "src", "alt", "title", "colspan", "rowspan"];
+
+/// URL schemes which run code or embed arbitrary content when opened.
+const UNSAFE_SCHEMES: &[&str] = &["javascript:", "vbscript:", "data:"];
+
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "wbr"];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "pre",
+    "ul",
+    "ol",
+    "li",
+    "table",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+];
+
+/// Extracts the statement in the element matched first by the selectors, as
+/// sanitized HTML.
+pub fn extract(text: &str, selectors: &[&str]) -> Option<String> {
+    let document = Html::parse_document(text);
+    selectors
+        .iter()
+        .find_map(|sel| document.select(&Selector::parse(sel).ok()?).next())
+        .map(sanitize)
+        .filter(|html| !html.trim().is_empty())
+}
+
+/// Serializes the contents of the element, dropping scripts, forms and unsafe
+/// attributes.
+pub fn sanitize(elem: ElementRef) -> String {
+    let mut html = String::new();
+    sanitize_children(elem, &mut html);
+    html
+}
+
+fn sanitize_children(elem: ElementRef, html: &mut String) {
+    for child in elem.children() {
+        match child.value() {
+            Node::Text(text) => html.push_str(&escape(text)),
+            Node::Element(e)
+                if DROPPED_ELEMENTS.contains(&e.name())
+                    || e.classes().any(|c| DROPPED_CLASSES.contains(&c)) => {}
+            Node::Element(e) => {
+                let name = e.name();
+                html.push('<');
+                html.push_str(name);
+                for (key, value) in e.attrs() {
+                    if KEPT_ATTRIBUTES.contains(&key) && !is_unsafe_url(value) {
+                        html.push_str(&format!(" {}=\"{}\"", key, escape(value)));
+                    }
+                }
+                html.push('>');
+
+                if !VOID_ELEMENTS.contains(&name) {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        sanitize_children(child, html);
+                    }
+                    html.push_str(&format!("</{}>", name));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the value is a URL with an unsafe scheme. Browsers ignore the case
+/// and whitespace or control characters in the scheme, and so does this.
+fn is_unsafe_url(value: &str) -> bool {
+    let value = value
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && !ch.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    UNSAFE_SCHEMES
+        .iter()
+        .any(|scheme| value.starts_with(scheme))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the statement as plain text for the terminal.
+pub fn render_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut text = String::new();
+    render_children(document.root_element(), false, &mut text);
+
+    // collapse blank lines made by nested blocks
+    let mut result = String::new();
+    let mut blank = true;
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            if !blank {
+                result.push('\n');
+            }
+            blank = true;
+        } else {
+            result.push_str(line);
+            result.push('\n');
+            blank = false;
+        }
+    }
+
+    format!("{}\n", result.trim_end())
+}
+
+fn render_children(elem: ElementRef, in_pre: bool, text: &mut String) {
+    for child in elem.children() {
+        match child.value() {
+            Node::Text(t) if in_pre => text.push_str(t),
+            Node::Text(t) => {
+                let collapsed = t.split_whitespace().collect::<Vec<_>>().join(" ");
+                if t.starts_with(char::is_whitespace) && !text.ends_with([' ', '\n']) {
+                    text.push(' ');
+                }
+                text.push_str(&collapsed);
+                if t.ends_with(char::is_whitespace) && !collapsed.is_empty() {
+                    text.push(' ');
+                }
+            }
+            Node::Element(e) => {
+                let child = match ElementRef::wrap(child) {
+                    Some(child) => child,
+                    None => continue,
+                };
+                let name = e.name();
+                if in_pre {
+                    // lines of samples may be wrapped in blocks (Codeforces)
+                    render_children(child, true, text);
+                    if BLOCK_ELEMENTS.contains(&name) && !text.ends_with('\n') {
+                        text.push('\n');
+                    }
+                    continue;
+                }
+
+                match name {
+                    "head" => continue,
+                    "sup" => text.push('^'),
+                    "br" => text.push('\n'),
+                    "li" => text.push_str("\n- "),
+                    "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => text.push_str("\n\n## "),
+                    "hr" => text.push_str("\n\n---\n\n"),
+                    _ if BLOCK_ELEMENTS.contains(&name) => text.push_str("\n\n"),
+                    _ => {}
+                }
+
+                render_children(child, in_pre || name == "pre", text);
+
+                if BLOCK_ELEMENTS.contains(&name) {
+                    text.push_str("\n\n");
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Saves the statement into the data directory and returns the path.
+pub fn save(html: &str) -> Result<PathBuf> {
+    let path = path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create `{}`", parent.display()))?;
+    }
+
+    let document = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        html
+    );
+    fs::write(&path, document).with_context(|| format!("failed to write `{}`", path.display()))?;
+
+    Ok(path)
+}
+
+pub fn path() -> PathBuf {
+    Path::new(DATA_DIR).join(STATEMENT_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_and_render() {
+        let text = r#"<div id="s"><h3>Problem <span class="btn-copy">Copy</span></h3>
+<p onclick="x()" class="c">Print <var>A</var> &lt; 10.</p><script>alert(1)</script>
+<pre>1 2
+3</pre></div>"#;
+        let html = extract(text, &["#none", "#s"]).unwrap();
+        assert_eq!(
+            html,
+            "<h3>Problem </h3>\n<p class=\"c\">Print <var>A</var> &lt; 10.</p>\n<pre>1 2\n3</pre>"
+        );
+        assert_eq!(
+            render_text(&html),
+            "## Problem\n\nPrint A < 10.\n\n1 2\n3\n"
+        );
+    }
+
+    #[test]
+    fn test_unsafe_url() {
+        let text = r#"<div id="s"><a href="JavaScript:x()">a</a><a href=" java&#9;script:x()">b</a>
+<a href="VBScript:x">c</a><img src="data:text/html,x"><a href="/tasks">d</a></div>"#;
+        assert_eq!(
+            extract(text, &["#s"]).unwrap(),
+            "<a>a</a><a>b</a>\n<a>c</a><img><a href=\"/tasks\">d</a>"
+        );
+    }
+}
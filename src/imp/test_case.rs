@@ -1,13 +1,14 @@
+use crate::eprintln_warning;
 use crate::imp;
+use crate::imp::case_info::CaseInfos;
 use crate::imp::config::CONFIG;
 use anyhow::{anyhow, bail, ensure};
 use anyhow::{Context as _, Result};
 use itertools::izip;
-use scopeguard::defer;
 use std::borrow::Cow;
-use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet};
 use std::io::{stdin, Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::thread;
 use std::{cmp, fmt, fs, iter, time};
@@ -518,6 +519,11 @@ impl TestCaseFile {
         Ok(idx)
     }
 
+    /// Returns the index of this test case, if it's named as usual.
+    pub fn index(&self) -> Option<i32> {
+        parse_if_name(&self.if_name)
+    }
+
     pub fn load_from(if_name: String, of_name: String) -> Result<TestCaseFile> {
         let if_contents = fs::read_to_string(&if_name)?;
         let of_contents = fs::read_to_string(&of_name)?;
@@ -560,6 +566,32 @@ impl TestCaseFile {
     pub fn get_output(&self) -> Result<Cow<'_, str>> {
         Ok(Cow::from(&self.of_contents))
     }
+
+    /// Checks if the expected output is already written.
+    pub fn has_output(&self) -> bool {
+        !self.of_contents.trim().is_empty()
+    }
+}
+
+/// Runs the command with the specified input and returns its standard output.
+/// It is an error unless the command exits successfully in time.
+pub fn run_for_output(
+    cmd: Command,
+    input: &[u8],
+    timeout: Option<time::Duration>,
+) -> Result<String> {
+    let timer = time::Instant::now();
+    let mut child = spawn(cmd)?;
+    input_to_child(&mut child, input)?;
+
+    let (_, result) = wait_or_timeout(timer, &mut child, timeout)?;
+    match result {
+        WaitResult::Output(stdout, _) => Ok(stdout),
+        WaitResult::TestResult(TestResult::RuntimeError(re)) => {
+            bail!("{}; stderr: {}", re.kind, re.stderr.trim())
+        }
+        WaitResult::TestResult(result) => bail!("{}", result.long_name()),
+    }
 }
 
 #[derive(Debug)]
@@ -613,59 +645,378 @@ pub fn add_test_case(if_contents: String, of_contents: String) -> Result<TestCas
     Ok(test_case)
 }
 
-/// Remove all specified test cases
+/// Splits the text into the input and the expected output at the first line
+/// equal to `separator`. The output is `None` if no separator line is found.
+pub fn split_input_output(text: &str, separator: &str) -> (String, Option<String>) {
+    let mut input = Vec::new();
+    let mut output = None;
+    for line in text.lines() {
+        match &mut output {
+            None if line.trim() == separator => output = Some(Vec::new()),
+            None => input.push(line),
+            Some(output) => output.push(line),
+        }
+    }
+
+    (
+        join_trimmed_lines(&input),
+        output.map(|output| join_trimmed_lines(&output)),
+    )
+}
+
+/// Joins lines into a text ending with a newline, dropping blank lines around
+/// it which are usually left when copying a sample from the browser.
+fn join_trimmed_lines(lines: &[&str]) -> String {
+    let is_blank = |line: &&str| line.trim().is_empty();
+    let start = lines.iter().position(|l| !is_blank(l));
+    let end = lines.iter().rposition(|l| !is_blank(l));
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end]
+            .iter()
+            .flat_map(|line| [line.trim_end(), "\n"])
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+/// Counts the number of test cases, i.e. the number of contiguous `inX.txt`
+/// starting from `in1.txt`.
+pub fn count_test_cases() -> i32 {
+    let mut count = 0;
+    while Path::new(&make_if_name(count + 1)).exists() {
+        count += 1;
+    }
+
+    count
+}
+
+/// Removes all specified test cases and renumbers the succeeding ones. The
+/// removed test cases are moved into the trash so that they can be restored by
+/// `restore_removed_test_cases()`.
 pub fn remove_test_cases(indices: &[i32]) -> Result<()> {
-    let test_case_files = RefCell::new(enumerate_test_case_files()?);
-
-    // Make sure to restore test cases before exit
-    defer! {
-        for (idx, test_case) in test_case_files.borrow().iter().enumerate() {
-            let idx = (idx + 1) as i32;
-            let test_case = TestCaseFile::new_with_idx(
-                idx,
-                test_case.if_contents.clone(),
-                test_case.of_contents.clone(),
-            );
-            let _ = test_case.write();
+    ensure!(!indices.is_empty(), "no test cases are specified");
+
+    let len = count_test_cases();
+    let err_indices = indices
+        .iter()
+        .filter(|idx| !(1..=len).contains(*idx))
+        .collect::<Vec<_>>();
+    if !err_indices.is_empty() {
+        bail!("some of indices are out of range: {:?}", err_indices);
+    }
+
+    let removed: BTreeSet<i32> = indices.iter().copied().collect();
+    for &idx in &removed {
+        ensure!(
+            Path::new(&make_of_name(idx)).exists(),
+            "mismatching test cases: `{}` exists but `{}` does not exist",
+            make_if_name(idx),
+            make_of_name(idx)
+        );
+    }
+
+    let trash = new_trash_dir()?;
+    let renames = removed.iter().flat_map(|&idx| {
+        [make_if_name(idx), make_of_name(idx)].map(|name| {
+            let dest = trash.join(&name);
+            (PathBuf::from(name), dest)
+        })
+    });
+    if let Err(e) = rename_all(renames) {
+        // nothing is moved into it
+        let _ = fs::remove_dir(&trash);
+        return Err(e);
+    }
+
+    let mut infos = CaseInfos::load()?;
+    let mut removed_infos = CaseInfos::default();
+    for &idx in &removed {
+        if let Some(info) = infos.take(idx) {
+            removed_infos.insert(idx, info);
         }
     }
+    removed_infos.save_to(&trash.join(TRASH_CASE_INFO_FILE))?;
+    infos.save()?;
+
+    let moves = (1..=len)
+        .filter(|idx| !removed.contains(idx))
+        .zip(1..)
+        .collect::<Vec<_>>();
+    renumber_test_cases(&moves).context("failed to renumber the remaining test cases")
+}
 
-    // !! BE CAREFUL !! Remove all test cases.
-    clean_test_cases(&test_case_files.borrow())?;
+/// Restores the test cases removed by the last `remove_test_cases()` at their
+/// original indices. Returns the restored indices.
+pub fn restore_removed_test_cases() -> Result<Vec<i32>> {
+    let trash = last_trash_dir()?.ok_or_else(|| anyhow!("there are no removed test cases"))?;
+    let mut removed = fs::read_dir(&trash)
+        .with_context(|| format!("failed to read the trash `{}`", trash.display()))?
+        .filter_map(|entry| parse_if_name(entry.ok()?.file_name().to_str()?))
+        .collect::<Vec<i32>>();
+    removed.sort_unstable();
+
+    // Decide the new order: removed test cases are inserted back at their
+    // original positions (or at the end, if there are not enough test cases
+    // left), and the others keep their relative order.
+    let len = count_test_cases();
+    let mut order = (1..=len).map(Some).collect::<Vec<_>>();
+    for &idx in &removed {
+        let pos = cmp::min(idx as usize - 1, order.len());
+        order.insert(pos, None);
+    }
 
-    let len = test_case_files.borrow().len() as i32;
-    let mut removed = 0;
-    let mut err_indices = Vec::new();
-    for &idx1 in indices {
-        // translate index into zero-origin
-        let idx0 = idx1 - 1;
+    // the test cases after a gap in the numbers are not moved; do not
+    // overwrite them.
+    let slots = order
+        .iter()
+        .zip(1..)
+        .filter(|(from, _)| from.is_none())
+        .map(|(_, to)| to)
+        .collect::<Vec<_>>();
+    for &to in &slots {
+        for name in [make_if_name(to), make_of_name(to)] {
+            ensure!(
+                to <= len || !Path::new(&name).exists(),
+                "cannot restore a test case as `{}`, which already exists",
+                name
+            );
+        }
+    }
 
-        if !(0..len).contains(&idx0) {
-            // translate index into one-origin
-            err_indices.push(idx1);
-            continue;
+    let moves = order
+        .iter()
+        .zip(1..)
+        .filter_map(|(from, to)| Some(((*from)?, to)))
+        .collect::<Vec<_>>();
+    renumber_test_cases(&moves).context("failed to make room for the restored test cases")?;
+
+    let trash_infos_path = trash.join(TRASH_CASE_INFO_FILE);
+    let mut trash_infos = CaseInfos::load_from(&trash_infos_path)?;
+    let mut infos = CaseInfos::load()?;
+    let mut restored = Vec::new();
+    for (idx, to) in removed.into_iter().zip(slots) {
+        rename_all([
+            (
+                trash.join(make_if_name(idx)),
+                PathBuf::from(make_if_name(to)),
+            ),
+            (
+                trash.join(make_of_name(idx)),
+                PathBuf::from(make_of_name(to)),
+            ),
+        ])
+        .with_context(|| format!("failed to restore test case {} as {}", idx, to))?;
+        if let Some(info) = trash_infos.take(idx) {
+            infos.insert(to, info);
         }
+        restored.push(to);
+    }
+    infos.save()?;
 
-        assert!(idx0 >= removed);
-        test_case_files.borrow_mut().remove((idx0 - removed) as _);
-        removed += 1;
+    if trash_infos_path.exists() {
+        fs::remove_file(&trash_infos_path)
+            .with_context(|| format!("failed to remove `{}`", trash_infos_path.display()))?;
     }
+    fs::remove_dir(&trash)
+        .with_context(|| format!("failed to remove the trash `{}`", trash.display()))?;
 
-    if !err_indices.is_empty() {
-        bail!("some of indices are out of range: {:?}", err_indices);
+    Ok(restored)
+}
+
+/// Moves the test case `from` to `to`, shifting the test cases in between.
+pub fn move_test_case(from: i32, to: i32) -> Result<()> {
+    let len = count_test_cases();
+    ensure!(
+        (1..=len).contains(&from),
+        "test case {} does not exist",
+        from
+    );
+    ensure!((1..=len).contains(&to), "test case {} does not exist", to);
+
+    let moves = if from < to {
+        iter::once((from, to))
+            .chain((from + 1..=to).map(|idx| (idx, idx - 1)))
+            .collect::<Vec<_>>()
+    } else {
+        iter::once((from, to))
+            .chain((to..from).map(|idx| (idx, idx + 1)))
+            .collect::<Vec<_>>()
+    };
+
+    renumber_test_cases(&moves)
+}
+
+/// Swaps the test case `a` and `b`.
+pub fn swap_test_cases(a: i32, b: i32) -> Result<()> {
+    renumber_test_cases(&[(a, b), (b, a)])
+}
+
+/// Renumbers test cases so that there are no missing numbers. Returns the pairs
+/// of the old index and the new index of the renumbered test cases.
+pub fn compact_test_cases() -> Result<Vec<(i32, i32)>> {
+    let names = fs::read_dir(".")
+        .context("failed to read the current directory")?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+
+    let orphans = names
+        .iter()
+        .filter_map(|name| parse_of_name(name))
+        .filter(|&idx| !Path::new(&make_if_name(idx)).exists())
+        .map(make_of_name)
+        .collect::<Vec<_>>();
+    ensure!(
+        orphans.is_empty(),
+        "mismatching test cases: no input exists for {:?}",
+        orphans
+    );
+
+    let mut indices = names
+        .iter()
+        .filter_map(|name| parse_if_name(name))
+        .collect::<Vec<_>>();
+    indices.sort_unstable();
+
+    let moves = indices
+        .into_iter()
+        .zip(1..)
+        .filter(|(from, to)| from != to)
+        .collect::<Vec<_>>();
+    renumber_test_cases(&moves)?;
+
+    Ok(moves)
+}
+
+/// Renumbers test cases at once; each element of `moves` is a pair of the
+/// current index and the new index. Files are first renamed to temporary names
+/// so that no test case is overwritten even if the indices are swapped.
+pub fn renumber_test_cases(moves: &[(i32, i32)]) -> Result<()> {
+    let moves = moves
+        .iter()
+        .copied()
+        .filter(|(from, to)| from != to)
+        .collect::<Vec<_>>();
+
+    // check conflicts before touching any files
+    let sources: HashSet<i32> = moves.iter().map(|&(from, _)| from).collect();
+    let mut dests = HashSet::new();
+    for &(from, to) in &moves {
+        ensure!(
+            Path::new(&make_if_name(from)).exists(),
+            "test case {} does not exist",
+            from
+        );
+        ensure!(
+            Path::new(&make_of_name(from)).exists(),
+            "mismatching test cases: `{}` exists but `{}` does not exist",
+            make_if_name(from),
+            make_of_name(from)
+        );
+        ensure!(to >= 1, "invalid test case index: {}", to);
+        ensure!(dests.insert(to), "test case {} is specified twice", to);
+        ensure!(
+            sources.contains(&to) || !Path::new(&make_if_name(to)).exists(),
+            "test case {} already exists",
+            to
+        );
     }
 
-    Ok(())
+    let mut infos = CaseInfos::load()?;
+    let to_tmp = moves.iter().flat_map(|&(from, _)| {
+        [make_if_name(from), make_of_name(from)].map(|name| {
+            let tmp = make_tmp_name(&name);
+            (name, tmp)
+        })
+    });
+    let from_tmp = moves.iter().flat_map(|&(from, to)| {
+        [
+            (make_tmp_name(&make_if_name(from)), make_if_name(to)),
+            (make_tmp_name(&make_of_name(from)), make_of_name(to)),
+        ]
+    });
+    rename_all(to_tmp.chain(from_tmp))?;
+
+    infos.renumber(&moves);
+    infos.save()
 }
 
-fn clean_test_cases(test_cases: &[TestCaseFile]) -> Result<()> {
-    for test_case in test_cases {
-        test_case.remove()?;
+/// Renames files in order. If one of them fails, the files already renamed
+/// are put back so that the test cases are left as they were.
+fn rename_all<P: AsRef<Path>>(renames: impl IntoIterator<Item = (P, P)>) -> Result<()> {
+    let mut done: Vec<(P, P)> = Vec::new();
+    for (from, to) in renames {
+        let (from_path, to_path) = (from.as_ref(), to.as_ref());
+        if let Err(e) = fs::rename(from_path, to_path) {
+            for (from, to) in done.iter().rev() {
+                let (from, to) = (from.as_ref(), to.as_ref());
+                if let Err(e) = fs::rename(to, from) {
+                    eprintln_warning!(
+                        "failed to put `{}` back to `{}`: {}",
+                        to.display(),
+                        from.display(),
+                        e
+                    );
+                }
+            }
+            return Err(e).with_context(|| {
+                format!(
+                    "failed to rename `{}` to `{}`",
+                    from_path.display(),
+                    to_path.display()
+                )
+            });
+        }
+        done.push((from, to));
     }
 
     Ok(())
 }
 
+/// Creates a new directory in the trash to store test cases removed at once.
+fn new_trash_dir() -> Result<PathBuf> {
+    let last = last_trash_dir()?
+        .and_then(|dir| dir.file_name()?.to_str()?.parse::<u32>().ok())
+        .unwrap_or(0);
+    let dir = trash_root().join((last + 1).to_string());
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create the trash `{}`", dir.display()))?;
+
+    Ok(dir)
+}
+
+/// Returns the directory in the trash storing the last removed test cases.
+fn last_trash_dir() -> Result<Option<PathBuf>> {
+    let root = trash_root();
+    if !root.exists() {
+        return Ok(None);
+    }
+
+    let last = fs::read_dir(&root)
+        .with_context(|| format!("failed to read the trash `{}`", root.display()))?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .max();
+
+    Ok(last.map(|last| root.join(last.to_string())))
+}
+
+const TRASH_CASE_INFO_FILE: &str = "cases.json";
+
+fn trash_root() -> PathBuf {
+    Path::new(imp::fs::DATA_DIR).join("trash")
+}
+
+fn parse_if_name(name: &str) -> Option<i32> {
+    name.strip_prefix("in")?.strip_suffix(".txt")?.parse().ok()
+}
+
+fn parse_of_name(name: &str) -> Option<i32> {
+    name.strip_prefix("out")?.strip_suffix(".txt")?.parse().ok()
+}
+
+fn make_tmp_name(name: &str) -> String {
+    format!(".{}.tmp", name)
+}
+
 fn make_if_name(num: i32) -> String {
     format!("in{}.txt", num)
 }
@@ -809,3 +1160,174 @@ fn wait_or_timeout(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lazy_static::lazy_static;
+    use scopeguard::defer;
+    use std::env;
+    use std::sync::{Mutex, PoisonError};
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_split_input_output() {
+        let (input, output) = split_input_output("\n3\n1 2 3\n---\n6\n\n", "---");
+        assert_eq!(input, "3\n1 2 3\n");
+        assert_eq!(output.as_deref(), Some("6\n"));
+
+        let (input, output) = split_input_output("1 2\r\n --- \r\n3\r\n", "---");
+        assert_eq!(input, "1 2\n");
+        assert_eq!(output.as_deref(), Some("3\n"));
+
+        let (input, output) = split_input_output("1 2\n", "---");
+        assert_eq!(input, "1 2\n");
+        assert_eq!(output, None);
+    }
+
+    lazy_static! {
+        // the test cases are always in the current directory, which is shared
+        // by all threads
+        static ref CURRENT_DIR: Mutex<()> = Mutex::new(());
+    }
+
+    /// Runs `f` in a new temporary directory containing the test cases whose
+    /// inputs are `inputs`.
+    fn with_test_cases(inputs: &[&str], f: impl FnOnce()) {
+        let _lock = CURRENT_DIR.lock().unwrap_or_else(PoisonError::into_inner);
+        let dir = TempDir::new("procon-assistant-test").unwrap();
+        let original_dir = env::current_dir().unwrap();
+        env::set_current_dir(dir.path()).unwrap();
+        defer! {
+            env::set_current_dir(&original_dir).unwrap();
+        }
+
+        for (idx, input) in (1..).zip(inputs) {
+            fs::write(make_if_name(idx), input).unwrap();
+            fs::write(make_of_name(idx), format!("out {}", input)).unwrap();
+        }
+
+        f();
+    }
+
+    /// The inputs of the test cases, checking that each output still matches.
+    fn inputs() -> Vec<String> {
+        (1..=count_test_cases())
+            .map(|idx| {
+                let input = fs::read_to_string(make_if_name(idx)).unwrap();
+                let output = fs::read_to_string(make_of_name(idx)).unwrap();
+                assert_eq!(output, format!("out {}", input));
+                input
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_remove_and_restore() {
+        with_test_cases(&["a", "b", "c", "d"], || {
+            remove_test_cases(&[3, 2]).unwrap();
+            assert_eq!(inputs(), ["a", "d"]);
+
+            remove_test_cases(&[1]).unwrap();
+            assert_eq!(inputs(), ["d"]);
+
+            // nothing is moved to the trash
+            assert!(remove_test_cases(&[]).is_err());
+            assert!(remove_test_cases(&[2]).is_err());
+
+            assert_eq!(restore_removed_test_cases().unwrap(), [1]);
+            assert_eq!(inputs(), ["a", "d"]);
+            assert_eq!(restore_removed_test_cases().unwrap(), [2, 3]);
+            assert_eq!(inputs(), ["a", "b", "c", "d"]);
+            assert!(restore_removed_test_cases().is_err());
+        });
+    }
+
+    #[test]
+    fn test_remove_and_restore_conflict() {
+        with_test_cases(&["a", "b", "c", "d"], || {
+            // half of a test case is missing; nothing is moved
+            fs::remove_file(make_of_name(2)).unwrap();
+            assert!(remove_test_cases(&[1, 2]).is_err());
+            assert!(Path::new(&make_if_name(1)).exists());
+            assert!(restore_removed_test_cases().is_err());
+            fs::write(make_of_name(2), "out b").unwrap();
+
+            remove_test_cases(&[3, 4]).unwrap();
+            assert_eq!(inputs(), ["a", "b"]);
+
+            // a test case after the gap is not overwritten
+            fs::write(make_if_name(4), "x").unwrap();
+            fs::write(make_of_name(4), "out x").unwrap();
+            assert!(restore_removed_test_cases().is_err());
+            assert_eq!(fs::read_to_string(make_if_name(4)).unwrap(), "x");
+            assert_eq!(inputs(), ["a", "b"]);
+
+            fs::remove_file(make_if_name(4)).unwrap();
+            fs::remove_file(make_of_name(4)).unwrap();
+            assert_eq!(restore_removed_test_cases().unwrap(), [3, 4]);
+            assert_eq!(inputs(), ["a", "b", "c", "d"]);
+        });
+    }
+
+    #[test]
+    fn test_move_and_swap() {
+        with_test_cases(&["a", "b", "c", "d"], || {
+            move_test_case(1, 3).unwrap();
+            assert_eq!(inputs(), ["b", "c", "a", "d"]);
+            move_test_case(4, 1).unwrap();
+            assert_eq!(inputs(), ["d", "b", "c", "a"]);
+            assert!(move_test_case(1, 5).is_err());
+
+            swap_test_cases(1, 4).unwrap();
+            assert_eq!(inputs(), ["a", "b", "c", "d"]);
+            swap_test_cases(2, 2).unwrap();
+            assert_eq!(inputs(), ["a", "b", "c", "d"]);
+            assert!(swap_test_cases(1, 5).is_err());
+        });
+    }
+
+    #[test]
+    fn test_renumber_collision() {
+        with_test_cases(&["a", "b", "c"], || {
+            // the destination is neither free nor moved away
+            assert!(renumber_test_cases(&[(1, 2)]).is_err());
+            assert!(renumber_test_cases(&[(1, 4), (2, 4)]).is_err());
+            assert!(renumber_test_cases(&[(1, 0)]).is_err());
+            assert_eq!(inputs(), ["a", "b", "c"]);
+
+            renumber_test_cases(&[(1, 2), (2, 3), (3, 1)]).unwrap();
+            assert_eq!(inputs(), ["c", "a", "b"]);
+        });
+    }
+
+    #[test]
+    fn test_compact() {
+        with_test_cases(&["a"], || {
+            fs::write("in3.txt", "b").unwrap();
+            fs::write("out3.txt", "out b").unwrap();
+            fs::write("in7.txt", "c").unwrap();
+            fs::write("out7.txt", "out c").unwrap();
+            assert_eq!(compact_test_cases().unwrap(), [(3, 2), (7, 3)]);
+            assert_eq!(inputs(), ["a", "b", "c"]);
+            assert!(compact_test_cases().unwrap().is_empty());
+
+            // an output without input is not moved
+            fs::write("out5.txt", "out x").unwrap();
+            assert!(compact_test_cases().is_err());
+            assert_eq!(inputs(), ["a", "b", "c"]);
+        });
+    }
+
+    #[test]
+    fn test_renumber_rollback() {
+        with_test_cases(&["a", "b"], || {
+            // `out3.txt` cannot be overwritten
+            fs::create_dir("out3.txt").unwrap();
+            assert!(renumber_test_cases(&[(1, 3), (2, 1)]).is_err());
+            assert_eq!(inputs(), ["a", "b"]);
+            assert!(!Path::new("in3.txt").exists());
+            assert!(!Path::new(&make_tmp_name("in1.txt")).exists());
+        });
+    }
+}
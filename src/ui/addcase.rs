@@ -1,22 +1,87 @@
-use crate::eprintln_tagged;
-use crate::imp::{process, test_case};
+use crate::imp::case_info::{self, Origin};
+use crate::imp::config::CONFIG;
+use crate::imp::test_case::TestCaseFile;
+use crate::imp::{clip, process, test_case};
 use crate::ExitStatus;
+use crate::{eprintln_info, eprintln_tagged, eprintln_warning};
 use anyhow::{Context, Result};
+use std::io::{self, Read};
 
 #[derive(clap::Parser)]
 #[clap(about = "Adds a new test case; creates `inX.txt` and `outX.txt` in the current directory")]
-pub struct AddCase;
+pub struct AddCase {
+    #[clap(
+        short = 'c',
+        long,
+        conflicts_with = "stdin",
+        help = "Reads the test case from the clipboard instead of opening the editor"
+    )]
+    from_clipboard: bool,
+
+    #[clap(
+        long,
+        help = "Reads the test case from the standard input instead of opening the editor"
+    )]
+    stdin: bool,
+}
 
 impl AddCase {
-    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
-        // Create and write an empty test case
-        let test_case = test_case::add_test_case(String::new(), String::new())
-            .context("failed to create a new test case")?;
-        eprintln_tagged!("Created": "{}, {}", test_case.if_name, test_case.of_name);
+    pub fn run(self, quiet: bool) -> Result<ExitStatus> {
+        let text = if self.from_clipboard {
+            Some(clip::get_clipboard().context("failed to read the clipboard")?)
+        } else if self.stdin {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .context("failed to read from stdin")?;
+            Some(text)
+        } else {
+            None
+        };
 
-        process::open_addcase(&[&test_case.if_name, &test_case.of_name], None)
-            .context("failed to open the generated file")?;
+        match text {
+            Some(text) => add_from_text(quiet, &text)?,
+            None => add_with_editor()?,
+        }
 
         Ok(ExitStatus::Success)
     }
 }
+
+fn add_with_editor() -> Result<()> {
+    // Create and write an empty test case
+    let test_case = test_case::add_test_case(String::new(), String::new())
+        .context("failed to create a new test case")?;
+    eprintln_tagged!("Created": "{}, {}", test_case.if_name, test_case.of_name);
+    record_manual_origin(&test_case);
+
+    process::open_addcase(&[&test_case.if_name, &test_case.of_name], None)
+        .context("failed to open the generated file")?;
+
+    Ok(())
+}
+
+fn add_from_text(quiet: bool, text: &str) -> Result<()> {
+    let separator = &CONFIG.addcase.separator;
+    let (input, output) = test_case::split_input_output(text, separator);
+    if output.is_none() && !quiet {
+        eprintln_info!(
+            "separator line `{}` not found; the whole text is used as the input",
+            separator
+        );
+    }
+
+    let test_case = test_case::add_test_case(input, output.unwrap_or_default())
+        .context("failed to create a new test case")?;
+    eprintln_tagged!("Created": "{}, {}", test_case.if_name, test_case.of_name);
+    record_manual_origin(&test_case);
+
+    Ok(())
+}
+
+fn record_manual_origin(test_case: &TestCaseFile) {
+    let indices = test_case.index().into_iter().collect::<Vec<_>>();
+    if let Err(e) = case_info::record_origin(&indices, Origin::Manual) {
+        eprintln_warning!("failed to record the origin of the test case: {}", e);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::imp::test_case;
+use crate::ExitStatus;
+use crate::{eprintln_info, eprintln_tagged};
+use anyhow::{Context, Result};
+
+#[derive(clap::Parser)]
+#[clap(about = "Renumbers test cases to fill the missing numbers")]
+pub struct Compact;
+
+impl Compact {
+    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        let moves = test_case::compact_test_cases().context("failed to renumber test cases")?;
+        if moves.is_empty() {
+            eprintln_info!("test cases are already numbered contiguously");
+        }
+
+        for (from, to) in moves {
+            eprintln_tagged!("Moved": "test case {} to {}", from, to);
+        }
+
+        Ok(ExitStatus::Success)
+    }
+}
@@ -1,19 +1,40 @@
+use crate::eprintln_tagged;
 use crate::imp::test_case;
 use crate::ExitStatus;
 use anyhow::{Context, Result};
 
 #[derive(clap::Parser)]
 #[clap(
-    about = "Deletes the specified test case; removes `inX.txt` and `outX.txt`, and decrement the case number of succeeding test cases"
+    about = "Deletes the specified test case; moves `inX.txt` and `outX.txt` to the trash, and decrement the case number of succeeding test cases"
 )]
 pub struct DelCase {
-    #[clap(help = "the list of test case numbers to remove")]
+    #[clap(
+        required_unless_present = "undo",
+        help = "the list of test case numbers to remove"
+    )]
     indices: Vec<i32>,
+
+    #[clap(
+        short,
+        long,
+        conflicts_with = "indices",
+        help = "Restores the test cases removed last"
+    )]
+    undo: bool,
 }
 
 impl DelCase {
     pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
-        test_case::remove_test_cases(&self.indices).context("failed to remove some test cases")?;
+        if self.undo {
+            let restored = test_case::restore_removed_test_cases()
+                .context("failed to restore the removed test cases")?;
+            eprintln_tagged!("Restored": "test cases {:?}", restored);
+        } else {
+            test_case::remove_test_cases(&self.indices)
+                .context("failed to remove some test cases")?;
+            eprintln_tagged!("Removed": "test cases {:?}", self.indices);
+        }
+
         Ok(ExitStatus::Success)
     }
 }
@@ -1,23 +1,81 @@
-use crate::eprintln_debug;
-use crate::eprintln_tagged;
-use crate::imp::download::ContestDescriptor;
-use crate::imp::fetch::TestCaseProvider;
-use crate::ui::fetch;
+use crate::imp::auth::SessionStatus;
+use crate::imp::config::CONFIG;
+use crate::imp::download::{ContestDescriptor, ContestProvider, Fetcher, Fetchers};
+use crate::imp::fetch::FetchedProblem;
+use crate::imp::fs::DATA_DIR;
+use crate::imp::langs::{self, Lang};
+use crate::imp::test_case::TestCaseFile;
+use crate::imp::{http, http_cache};
+use crate::ui::print_macros::TAG_WIDTH;
+use crate::ui::{fetch, init};
 use crate::ExitStatus;
+use crate::{eprintln_debug, eprintln_error, eprintln_info, eprintln_tagged, eprintln_warning};
+use anyhow::ensure;
 use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use console::style;
+use reqwest::StatusCode;
 use scopeguard::defer;
-use std::path::Path;
-use std::{env, str};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use std::{env, fs, str};
+
+/// Kept in the contest directory.
+const FAILED_PROBLEMS_FILE: &str = "failed_problems.json";
+/// Kept in the contest directory and shared by the projects as the build cache.
+const SHARED_CACHE_DIR: &str = "target";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
 
 #[derive(clap::Parser)]
 #[clap(about = "Fetches sample cases of all problems in a contest")]
 pub struct Download {
     #[clap(help = "The contest-descriptor of the target. ex) atcoder:abc012")]
     contest_descriptor: Option<String>,
+
+    #[clap(
+        short,
+        long,
+        default_value = "4",
+        help = "The number of problems fetched at the same time"
+    )]
+    jobs: usize,
+
+    #[clap(
+        long,
+        help = "Fetches only the problems which failed in the last download of the contest"
+    )]
+    retry_failed: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "Waits for the contest to start and its problems to be listed"
+    )]
+    wait: bool,
+
+    #[clap(short, long, help = "The lang to init in each problem directory")]
+    lang: Option<String>,
+
+    #[clap(
+        long,
+        conflicts_with = "lang",
+        help = "Does not init projects in the problem directories"
+    )]
+    no_init: bool,
+
+    #[clap(long, help = "Ignores the cached pages and fetches them again")]
+    refresh: bool,
 }
 
 impl Download {
     pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        http_cache::set_refresh(self.refresh);
+
         let dsc = parse_descriptor(self.contest_descriptor)
             .context("failed to parse contest-descriptor")?;
         let contest_provider = dsc.clone().resolve_provider().with_context(|| {
@@ -26,22 +84,243 @@ impl Download {
                 dsc
             )
         })?;
-        let fetchers = contest_provider
-            .make_fetchers()
-            .context("failed to make the fetcher")?;
+        check_session(&*contest_provider, self.wait);
+        let fetchers = if self.wait {
+            wait_for_fetchers(&*contest_provider)?
+        } else {
+            contest_provider
+                .make_fetchers()
+                .context("failed to make the fetcher")?
+        };
 
         eprintln_tagged!("Fetching": "{} (at {})", contest_provider.contest_id(), contest_provider.url());
         fetchers.prepare_generate()?;
         eprintln_debug!("fetchers: {:?}", fetchers.fetchers);
-        for fetcher in fetchers.fetchers {
-            generate_one(&fetcher.problem_name, fetcher.provider)?;
+
+        let mut targets = fetchers.fetchers;
+        if self.retry_failed {
+            let failed = load_failed_problems().context("failed to load the failed problems")?;
+            ensure!(
+                !failed.is_empty(),
+                "no failed problems are recorded for this contest"
+            );
+            targets.retain(|fetcher| failed.contains(&fetcher.problem_name));
+        }
+
+        let problem_names: Vec<_> = targets.iter().map(|f| f.problem_name.clone()).collect();
+        let mut failed = Vec::new();
+        for (problem_name, result) in fetch_all(targets, self.jobs.max(1)) {
+            if let Err(e) = result.and_then(|problem| write_one(&problem_name, problem)) {
+                eprintln_error!("failed to fetch problem {}: {:#}", problem_name, e);
+                failed.push(problem_name);
+            }
+        }
+
+        save_failed_problems(&failed).context("failed to record the failed problems")?;
+
+        // the project does not depend on the sample cases; init the problems
+        // failed to fetch as well.
+        let init_failed = !self.no_init
+            && match init_lang(self.lang.as_ref()) {
+                Ok(lang) => init_all(&*lang, &problem_names, self.jobs.max(1)),
+                Err(e) => {
+                    eprintln_error!("failed to init the projects: {:#}", e);
+                    true
+                }
+            };
+
+        if !failed.is_empty() {
+            eprintln_error!(
+                "failed to fetch {}; retry them with `--retry-failed`",
+                failed.join(", ")
+            );
+        }
+
+        if failed.is_empty() && !init_failed {
+            Ok(ExitStatus::Success)
+        } else {
+            Ok(ExitStatus::Failure)
+        }
+    }
+}
+
+/// Resolved only when the projects are initialized, so that a bad
+/// `init.default_lang` does not stop fetching.
+fn init_lang(lang: Option<&String>) -> Result<Box<dyn Lang>> {
+    let specified_lang = lang.unwrap_or(&CONFIG.init.default_lang);
+    langs::get_from_alias(specified_lang).context("failed to get the language")
+}
+
+/// Waits until the contest starts and its problems are listed. Gives up when
+/// the problems are not listed within the grace period after the start.
+fn wait_for_fetchers(provider: &dyn ContestProvider) -> Result<Fetchers> {
+    match provider.start_time() {
+        Ok(Some(start_time)) => wait_until(start_time),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln_warning!("failed to get the start time of the contest: {:#}", e);
+        }
+    }
+
+    let grace_minutes = CONFIG.download.wait_grace_minutes;
+    let deadline = Instant::now() + Duration::from_secs(grace_minutes * 60);
+    loop {
+        match provider.make_fetchers() {
+            Ok(fetchers) if !fetchers.fetchers.is_empty() => return Ok(fetchers),
+            Ok(_) => {
+                eprintln_info!("no problems are listed yet");
+            }
+            Err(e) if is_not_published(&e) => {
+                eprintln_info!("problems are not available yet: {:#}", e);
+            }
+            Err(e) => return Err(e).context("failed to make the fetcher"),
+        }
+
+        ensure!(
+            Instant::now() < deadline,
+            "the problems are not listed {} minutes after the start; giving up",
+            grace_minutes
+        );
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Whether the error may go away once the problems are published: the pages
+/// of a contest not started yet are not found (or forbidden), and the site may
+/// be overloaded at the start.
+fn is_not_published(e: &anyhow::Error) -> bool {
+    let not_found = e
+        .chain()
+        .filter_map(|e| e.downcast_ref::<reqwest::Error>())
+        .any(|e| {
+            matches!(
+                e.status(),
+                Some(StatusCode::NOT_FOUND | StatusCode::FORBIDDEN)
+            )
+        });
+
+    not_found || http::is_transient(e)
+}
+
+/// Warns before fetching if the saved session has expired. Not being logged in
+/// matters only for a contest not started yet, whose problems may be visible
+/// only to the participants.
+fn check_session(provider: &dyn ContestProvider, wait: bool) {
+    match provider.session_status() {
+        Ok(Some(SessionStatus::LoggedIn { username })) => {
+            eprintln_info!("logged in to {} as {}", provider.site_name(), username);
+        }
+        Ok(Some(SessionStatus::Expired)) => {
+            eprintln_warning!(
+                "the session of {} has expired; log in again to see the problems",
+                provider.site_name()
+            );
         }
+        Ok(Some(SessionStatus::NotLoggedIn)) if wait => {
+            eprintln_warning!(
+                "not logged in to {}; log in to see the problems",
+                provider.site_name()
+            );
+        }
+        Ok(Some(SessionStatus::NotLoggedIn)) | Ok(None) => {}
+        Err(e) => {
+            eprintln_warning!("failed to check the session: {:#}", e);
+        }
+    }
+}
 
-        Ok(ExitStatus::Success)
+fn wait_until(start_time: DateTime<FixedOffset>) {
+    eprintln_tagged!("Waiting": "for the contest to start at {}", start_time.with_timezone(&Local));
+    loop {
+        let remaining = match (start_time.with_timezone(&Utc) - Utc::now()).to_std() {
+            Ok(remaining) if !remaining.is_zero() => remaining,
+            _ => break,
+        };
+
+        let secs = remaining.as_secs();
+        eprint!(
+            "\r{:>width$} {:02}:{:02}:{:02} ",
+            style("Countdown").cyan().bold(),
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            width = TAG_WIDTH
+        );
+        thread::sleep(remaining.min(Duration::from_secs(1)));
+    }
+    eprintln!();
+}
+
+/// Inits the projects in the problem directories, `jobs` at a time, sharing
+/// the build cache kept in the contest directory. Returns whether any of them
+/// failed.
+fn init_all(lang: &dyn Lang, problem_names: &[String], jobs: usize) -> bool {
+    eprintln_tagged!("Initializing": "{} projects in {}", problem_names.len(), lang.get_lang_name());
+    let shared_cache = Path::new(DATA_DIR).join(SHARED_CACHE_DIR);
+    let done = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+
+    for chunk in problem_names.chunks(jobs) {
+        thread::scope(|s| {
+            for problem_name in chunk {
+                let dir = Path::new(problem_name);
+                let progress = lang.init_async(dir, Some(&shared_cache));
+                let (done, failed) = (&done, &failed);
+                s.spawn(move || {
+                    let result = init::wait_for_init(dir, progress);
+                    let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+                    match result {
+                        Ok(()) => {
+                            eprintln_tagged!("Initialized": "{} ({}/{})", problem_name, done, problem_names.len());
+                        }
+                        Err(e) => {
+                            eprintln_error!("failed to init problem {}: {:#}", problem_name, e);
+                            failed.store(true, Ordering::SeqCst);
+                        }
+                    }
+                });
+            }
+        });
     }
+
+    failed.into_inner()
+}
+
+/// Fetches the problems with `jobs` threads and returns the results in the
+/// original order. Problems are only fetched here; nothing is written.
+/// Problems with no remote source are skipped.
+fn fetch_all(fetchers: Vec<Fetcher>, jobs: usize) -> Vec<(String, Result<FetchedProblem>)> {
+    let queue = Mutex::new(fetchers.into_iter().enumerate().collect::<VecDeque<_>>());
+    let results = Mutex::new(Vec::new());
+
+    thread::scope(|s| {
+        for _ in 0..jobs {
+            s.spawn(|| loop {
+                let (idx, fetcher) = match queue.lock().unwrap().pop_front() {
+                    Some(item) => item,
+                    None => break,
+                };
+                let result = match &fetcher.provider {
+                    Some(provider) => fetch::fetch_problem(&**provider),
+                    None => continue,
+                };
+                results
+                    .lock()
+                    .unwrap()
+                    .push((idx, fetcher.problem_name, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|&(idx, _, _)| idx);
+    results
+        .into_iter()
+        .map(|(_, problem_name, result)| (problem_name, result))
+        .collect()
 }
 
-pub fn generate_one(problem: &str, provider: Box<dyn TestCaseProvider>) -> Result<()> {
+fn write_one(problem: &str, fetched: FetchedProblem) -> Result<()> {
     // Chdir to the directory for individual problem. Use defer! to ensure that the current
     // directory is restored before ending.
     let current_dir = env::current_dir().expect("critical error: failed to get current directory");
@@ -50,12 +329,53 @@ pub fn generate_one(problem: &str, provider: Box<dyn TestCaseProvider>) -> Resul
          env::set_current_dir(current_dir).expect("critical error: failed to chdir");
     }
 
-    let test_cases = fetch::fetch_test_case_files(provider).context("failed to read test cases")?;
+    // test cases are numbered while fetching in the contest directory, so
+    // renumber them for the problem directory.
+    let idx_start = TestCaseFile::next_unused_idx().context("failed to get unused index")?;
+    let test_cases = fetched
+        .test_cases
+        .into_iter()
+        .zip(idx_start..)
+        .map(|(tc, idx)| TestCaseFile::new_with_idx(idx, tc.if_contents, tc.of_contents))
+        .collect();
+
     fetch::write_test_case_files(test_cases).context("failed to write test cases")?;
+    fetch::save_statement(fetched.statement);
+    fetch::save_info(&fetched.info);
 
     Ok(())
 }
 
+fn failed_problems_path() -> PathBuf {
+    Path::new(DATA_DIR).join(FAILED_PROBLEMS_FILE)
+}
+
+fn load_failed_problems() -> Result<Vec<String>> {
+    let path = failed_problems_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let text = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read `{}`", path.display()))?;
+    serde_json::from_str(&text).with_context(|| format!("failed to parse `{}`", path.display()))
+}
+
+fn save_failed_problems(failed: &[String]) -> Result<()> {
+    let path = failed_problems_path();
+    if failed.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("failed to remove `{}`", path.display()))?;
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(DATA_DIR).with_context(|| format!("failed to create `{}`", DATA_DIR))?;
+    let text = serde_json::to_string_pretty(failed).context("failed to serialize")?;
+    fs::write(&path, text).with_context(|| format!("failed to write `{}`", path.display()))
+}
+
 #[allow(clippy::unnecessary_wraps)]
 fn handle_empty_arg() -> Result<ContestDescriptor> {
     fn handle_empty_arg_impl() -> Option<ContestDescriptor> {
@@ -66,11 +386,8 @@ fn handle_empty_arg() -> Result<ContestDescriptor> {
             .and_then(OsStr::to_str)
             .map(ToString::to_string)?;
 
-        if ["abc", "arc", "agc"].contains(&&file_name[0..3]) {
-            Some(ContestDescriptor::new("atcoder".to_string(), file_name))
-        } else {
-            None
-        }
+        fetch::is_atcoder_contest_id(&file_name)
+            .then(|| ContestDescriptor::new("atcoder".to_string(), file_name))
     }
 
     let dsc = handle_empty_arg_impl()
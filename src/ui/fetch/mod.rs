@@ -1,37 +1,83 @@
-use crate::eprintln_tagged;
-use crate::imp::fetch::{ProblemDescriptor, TestCaseProvider};
+use crate::imp::case_info::{self, Origin};
+use crate::imp::fetch::atcoder;
+use crate::imp::fetch::file::File;
+use crate::imp::fetch::{FetchedProblem, ProblemDescriptor, TestCaseProvider};
+use crate::imp::http_cache;
+use crate::imp::problem_info::ProblemInfo;
+use crate::imp::statement;
 use crate::imp::test_case::TestCaseFile;
 use crate::ExitStatus;
-use anyhow::bail;
+use crate::{eprintln_tagged, eprintln_warning};
+use anyhow::{bail, ensure};
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::env;
-use std::ffi::OsStr;
+
+lazy_static! {
+    // well-known AtCoder contests which can be recognized without `atcoder` directory
+    static ref RE_ATCODER_CONTEST_ID: Regex = Regex::new(
+        r"^(?:(?:abc|arc|agc|ahc)\d{3}|abs|typical90|practice2?|past\d{6}-open|tessoku-book)$"
+    )
+    .unwrap();
+}
+
+/// Whether the directory name is a well-known AtCoder contest id.
+pub fn is_atcoder_contest_id(name: &str) -> bool {
+    RE_ATCODER_CONTEST_ID.is_match(name)
+}
 
 #[derive(clap::Parser)]
 #[clap(about = "Fetches sample cases of a problem")]
 pub struct Fetch {
-    #[clap(help = "The problem-descriptor of the target problem. ex) aoj:0123, atcoder:abc012a")]
+    #[clap(
+        help = "The problem-descriptor of the target problem. ex) aoj:0123, atcoder:abc012a, atcoder:typical90/a"
+    )]
     problem_descriptor: Option<String>,
+
+    #[clap(
+        short,
+        long,
+        help = "The site whose parser is used for `file:` or `-`; detected from the document by default"
+    )]
+    site: Option<String>,
+
+    #[clap(long, help = "Ignores the cached pages and fetches them again")]
+    refresh: bool,
 }
 
 impl Fetch {
     pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        http_cache::set_refresh(self.refresh);
         let dsc = parse_descriptor(self.problem_descriptor)
             .context("failed to parse problem descriptor")?;
-        let provider = dsc.clone().resolve_provider().with_context(|| {
-            format!(
-                "failed to resolve problem provider from problem-descriptor `{:?}`",
-                dsc
-            )
-        })?;
-        let tcfs = fetch_test_case_files(provider)?;
-        write_test_case_files(tcfs)?;
+        let provider = match self.site {
+            Some(site) => {
+                ensure!(
+                    dsc.contest_site == "file",
+                    "`--site` is only for a local file or stdin"
+                );
+                let provider = File::new(dsc.problem_id, Some(&site))
+                    .context("failed to create the provider File")?;
+                Box::new(provider) as _
+            }
+            None => dsc.clone().resolve_provider().with_context(|| {
+                format!(
+                    "failed to resolve problem provider from problem-descriptor `{:?}`",
+                    dsc
+                )
+            })?,
+        };
+        let problem = fetch_problem(&*provider)?;
+        write_test_case_files(problem.test_cases)?;
+        save_statement(problem.statement);
+        save_info(&problem.info);
 
         Ok(ExitStatus::Success)
     }
 }
 
-pub fn fetch_test_case_files(provider: Box<dyn TestCaseProvider>) -> Result<Vec<TestCaseFile>> {
+pub fn fetch_problem(provider: &dyn TestCaseProvider) -> Result<FetchedProblem> {
     eprintln_tagged!(
         "Fetching": "{} id {} (at {})",
         provider.site_name(),
@@ -39,64 +85,114 @@ pub fn fetch_test_case_files(provider: Box<dyn TestCaseProvider>) -> Result<Vec<
         provider.url()
     );
 
-    let test_case_files = provider
-        .fetch_test_case_files()
+    let problem = provider
+        .fetch_problem()
         .context("failed to fetch test case")?;
+    if problem.test_cases.is_empty() {
+        eprintln_warning!(
+            "no sample cases found at {}; the page layout may not be supported",
+            provider.url()
+        );
+    }
 
-    Ok(test_case_files)
+    Ok(problem)
 }
 
 pub fn write_test_case_files(test_cases: Vec<TestCaseFile>) -> Result<()> {
     let num_test_cases = test_cases.len();
+    let mut indices = Vec::new();
     for test_case in test_cases {
         eprintln_tagged!("Generating": "Sample Case: {}", test_case);
         test_case
             .write()
             .with_context(|| format!("failed to write test case file: `{}`", test_case))?;
+        indices.extend(test_case.index());
+    }
+    if let Err(e) = case_info::record_origin(&indices, Origin::Fetched) {
+        eprintln_warning!("failed to record the origin of test cases: {}", e);
     }
     eprintln_tagged!("Finished": "generating {} Sample Case(s)", num_test_cases);
 
     Ok(())
 }
 
+/// Saves the problem information such as the time limit for `run` command.
+pub fn save_info(info: &ProblemInfo) {
+    if info.is_empty() {
+        return;
+    }
+
+    if let Err(e) = info.save() {
+        eprintln_warning!("failed to save the problem information: {}", e);
+        return;
+    }
+
+    let limit = |limit: Option<u64>, unit: &str| match limit {
+        Some(limit) => format!("{} {}", limit, unit),
+        None => "unknown".to_string(),
+    };
+    eprintln_tagged!(
+        "Saved": "problem info (time limit: {}, memory limit: {})",
+        limit(info.time_limit_milliseconds, "ms"),
+        limit(info.memory_limit_megabytes, "MB")
+    );
+}
+
+/// Saves the statement for `statement` command. Failure is not fatal since
+/// the samples are already written.
+pub fn save_statement(statement: Option<String>) {
+    let statement = match statement {
+        Some(statement) => statement,
+        None => return,
+    };
+
+    match statement::save(&statement) {
+        Ok(path) => {
+            eprintln_tagged!("Saved": "problem statement to {}", path.display());
+        }
+        Err(e) => {
+            eprintln_warning!("failed to save the problem statement: {}", e);
+        }
+    }
+}
+
+/// Guesses the problem from the current directory. The directory is expected
+/// to be `<contest-id>/<problem-name>` as made by `download`, and the contest
+/// site is taken from an ancestor directory (or guessed from the contest id).
 fn handle_empty_arg() -> Result<ProblemDescriptor> {
     let current_dir = env::current_dir().expect("critical error: failed to get current directory");
+    let names = current_dir
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect::<Vec<_>>();
 
     // sometimes current directory has no name (for exampple: root directory)
-    let maybe_current_dir_name = current_dir
-        .file_name()
-        .and_then(OsStr::to_str)
-        .map(ToString::to_string);
-
-    if let Some(current_dir_name) = maybe_current_dir_name {
-        let mut contest_site = None;
-        let problem_id_or_name = current_dir_name;
-        let mut contest_id = None;
-
-        for component in current_dir.components() {
-            match component.as_os_str().to_str() {
-                Some("aoj") => contest_site = Some("aoj".to_string()),
-                Some("atcoder") | Some("at") => contest_site = Some("atcoder".to_string()),
-                Some(other) if ["abc", "arc", "agc"].iter().any(|p| other.starts_with(p)) => {
-                    contest_id = Some(other.to_string())
-                }
-                _ => continue,
-            }
-        }
+    if let [ancestors @ .., contest_id, problem_name] = &names[..] {
+        let contest_site = ancestors
+            .iter()
+            .rev()
+            .find_map(|&name| match name {
+                "aoj" => Some("aoj"),
+                "atcoder" | "at" => Some("atcoder"),
+                "codeforces" | "cf" => Some("codeforces"),
+                _ => None,
+            })
+            .or_else(|| is_atcoder_contest_id(contest_id).then_some("atcoder"));
 
-        match (contest_site, contest_id) {
-            (Some(contest_site), Some(contest_id)) => {
-                let problem_name = problem_id_or_name;
-                let problem_id = format!("{}{}", contest_id, problem_name);
-                return Ok(ProblemDescriptor::new(contest_site, problem_id));
+        match contest_site {
+            Some("aoj") => {
+                let problem_id = problem_name.to_string();
+                return Ok(ProblemDescriptor::new("aoj".to_string(), problem_id));
+            }
+            Some("atcoder") => {
+                // the task list is looked up only if the task URL is unknown
+                let problem_id = atcoder::abc_style_problem_id(contest_id, problem_name)
+                    .unwrap_or_else(|| format!("{}/{}", contest_id, problem_name));
+                return Ok(ProblemDescriptor::new("atcoder".to_string(), problem_id));
             }
-            (Some(contest_site), None)
-                if ["abc", "arc", "agc"]
-                    .iter()
-                    .any(|p| contest_site.starts_with(p)) =>
-            {
-                let problem_id = problem_id_or_name;
-                return Ok(ProblemDescriptor::new(contest_site, problem_id));
+            Some("codeforces") if contest_id.chars().all(|ch| ch.is_ascii_digit()) => {
+                let problem_id = format!("{}{}", contest_id, problem_name);
+                return Ok(ProblemDescriptor::new("codeforces".to_string(), problem_id));
             }
             _ => {}
         }
@@ -107,6 +203,7 @@ fn handle_empty_arg() -> Result<ProblemDescriptor> {
 
 fn parse_descriptor(dsc: Option<String>) -> Result<ProblemDescriptor> {
     match dsc {
+        Some(dsc) if dsc == "-" => Ok(ProblemDescriptor::new("file".to_string(), dsc)),
         Some(dsc) => ProblemDescriptor::parse(&dsc).context("failed parse problem descriptor"),
         None => handle_empty_arg(),
     }
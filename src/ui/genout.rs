@@ -0,0 +1,162 @@
+use crate::imp::langs;
+use crate::imp::test_case;
+use crate::imp::test_case::TestCaseFile;
+use crate::ui::compile;
+use crate::ExitStatus;
+use crate::{eprintln_info, eprintln_tagged};
+use anyhow::ensure;
+use anyhow::{Context, Result};
+use scopeguard::defer;
+use std::cell::RefCell;
+use std::process::Command;
+use std::{env, time};
+
+#[derive(clap::Parser)]
+#[clap(
+    about = "Generates expected outputs of test cases by running a reference solution; only test cases with empty `outX.txt` are filled"
+)]
+pub struct GenOut {
+    #[clap(
+        help = "The directory of the reference solution project",
+        conflicts_with = "command"
+    )]
+    reference_dir: Option<String>,
+
+    #[clap(
+        short,
+        long,
+        help = "The lang of the reference solution; guessed if not specified"
+    )]
+    lang: Option<String>,
+
+    #[clap(short, long, help = "Compiles the reference solution in release mode")]
+    release_compile: bool,
+
+    #[clap(
+        short,
+        long = "timeout",
+        help = "Timeout milliseconds for each test case; no timeout by default"
+    )]
+    timeout_milliseconds: Option<u64>,
+
+    #[clap(
+        short,
+        long,
+        help = "Regenerates the expected outputs even if they already exist"
+    )]
+    force: bool,
+
+    #[clap(
+        last = true,
+        help = "The command to run as a reference solution, instead of a project"
+    )]
+    command: Vec<String>,
+}
+
+impl GenOut {
+    pub fn run(self, quiet: bool) -> Result<ExitStatus> {
+        let mut test_cases = test_case::enumerate_test_case_files()
+            .context("failed to enumerate test cases")?
+            .into_iter()
+            .filter(|tc| self.force || !tc.has_output())
+            .collect::<Vec<_>>();
+
+        if test_cases.is_empty() {
+            eprintln_info!("all test cases already have expected outputs");
+            return Ok(ExitStatus::Success);
+        }
+
+        let timeout = self.timeout_milliseconds.map(time::Duration::from_millis);
+        if let Some(reference_dir) = &self.reference_dir {
+            self.generate_by_project(quiet, reference_dir, timeout, &mut test_cases)?;
+        } else {
+            ensure!(
+                !self.command.is_empty(),
+                "neither the reference solution directory nor the command is specified"
+            );
+
+            generate_all(&mut test_cases, timeout, || {
+                let mut cmd = Command::new(&self.command[0]);
+                cmd.args(&self.command[1..]);
+                Ok(cmd)
+            })?;
+        }
+
+        for test_case in &test_cases {
+            test_case
+                .write()
+                .with_context(|| format!("failed to write test case file: `{}`", test_case))?;
+            eprintln_tagged!("Generated": "{}", test_case.of_name);
+        }
+
+        Ok(ExitStatus::Success)
+    }
+
+    fn generate_by_project(
+        &self,
+        quiet: bool,
+        reference_dir: &str,
+        timeout: Option<time::Duration>,
+        test_cases: &mut [TestCaseFile],
+    ) -> Result<()> {
+        // the reference solution is compiled and run in its own directory, so
+        // restore the current directory afterwards. test cases are already
+        // loaded into memory and they are written after this.
+        let original_dir = RefCell::new(None);
+        defer! {
+            if let Some(original_dir) = &*original_dir.borrow() {
+                env::set_current_dir(original_dir)
+                    .expect("critical error: failed to restore original directory");
+            }
+        }
+
+        *original_dir.borrow_mut() =
+            Some(env::current_dir().expect("critical error: failed to get current directory"));
+        env::set_current_dir(reference_dir).with_context(|| {
+            format!(
+                "failed to enter the reference solution directory `{}`",
+                reference_dir
+            )
+        })?;
+
+        let lang = match &self.lang {
+            Some(lang) => langs::get_from_alias(lang).context("failed to get the language")?,
+            None => langs::guess_lang()
+                .context("failed to guess the language of the reference solution")?,
+        };
+        if !quiet {
+            eprintln_info!("reference solution language: {}", lang.get_lang_name());
+        }
+
+        let status = compile::compile(quiet, self.release_compile, &*lang, false)
+            .context("failed to compile the reference solution")?;
+        ensure!(
+            status == ExitStatus::Success,
+            "failed to compile the reference solution"
+        );
+
+        generate_all(test_cases, timeout, || {
+            if self.release_compile {
+                lang.release_run_command()
+            } else {
+                lang.run_command()
+            }
+        })
+    }
+}
+
+fn generate_all(
+    test_cases: &mut [TestCaseFile],
+    timeout: Option<time::Duration>,
+    make_command: impl Fn() -> Result<Command>,
+) -> Result<()> {
+    eprintln_tagged!("Running": "reference solution for {} test cases", test_cases.len());
+    for test_case in test_cases {
+        let cmd = make_command().context("failed to get the command to run")?;
+        let output = test_case::run_for_output(cmd, test_case.if_contents.as_bytes(), timeout)
+            .with_context(|| format!("reference solution failed for `{}`", test_case))?;
+        test_case.of_contents = output;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use crate::imp::config::CONFIG;
 use crate::imp::langs;
+use crate::imp::langs::Lang;
+use crate::imp::progress::Progress;
 use crate::ui::open;
 use crate::ExitStatus;
 use crate::{eprintln_debug, eprintln_progress};
@@ -7,7 +9,7 @@ use anyhow::{anyhow, bail};
 use anyhow::{Context, Result};
 use scopeguard::defer;
 use std::cell::RefCell;
-use std::path::MAIN_SEPARATOR;
+use std::path::{Path, MAIN_SEPARATOR};
 use std::{env, fs};
 
 #[derive(clap::Parser)]
@@ -63,17 +65,7 @@ impl Init {
                 .expect("critical error: failed to set current directory to the project directory");
         }
 
-        // initialize the project asynchronously and get progress
-        let progress = lang.init_async();
-        while let Ok(msg) = progress.recver.recv() {
-            eprintln_progress!("{}", msg);
-        }
-
-        progress
-            .handle
-            .join()
-            .map_err(|_| anyhow!("init thread panicked"))?
-            .context("init failed")?;
+        init_project(&*lang, Path::new("."), None)?;
 
         if CONFIG.init.auto_open {
             open::open(quiet).context("failed to open the generated project")?;
@@ -82,3 +74,27 @@ impl Init {
         Ok(ExitStatus::Success)
     }
 }
+
+/// Initializes the project of the language in `dir`. `shared_cache` is the
+/// build cache shared with other projects, if any.
+pub fn init_project(lang: &dyn Lang, dir: &Path, shared_cache: Option<&Path>) -> Result<()> {
+    // initialize the project asynchronously and get progress
+    wait_for_init(dir, lang.init_async(dir, shared_cache))
+}
+
+/// Prints the progress of initializing the project in `dir` until it ends.
+pub fn wait_for_init(dir: &Path, progress: Progress<Result<()>>) -> Result<()> {
+    while let Ok(msg) = progress.recver.recv() {
+        if dir == Path::new(".") {
+            eprintln_progress!("{}", msg);
+        } else {
+            eprintln_progress!("{}: {}", dir.display(), msg);
+        }
+    }
+
+    progress
+        .handle
+        .join()
+        .map_err(|_| anyhow!("init thread panicked"))?
+        .context("init failed")
+}
@@ -1,19 +1,28 @@
-use super::LoginUi;
+use super::{Credentials, LoginUi};
+use crate::eprintln_tagged;
+use crate::imp::auth;
 use anyhow::{Context, Result};
 
 #[derive(clap::Parser)]
-#[clap(about = "Logs in to Aoj")]
-pub struct Aoj;
+#[clap(about = "Logs in to Aizu Online Judge")]
+pub struct Aoj {
+    #[clap(flatten)]
+    credentials: Credentials,
+}
 
 impl Aoj {
     pub fn run(self, quiet: bool) -> Result<()> {
-        Aoj.authenticate(quiet).context("failed to login")
+        self.authenticate(quiet).context("failed to login")
     }
 }
 
 impl LoginUi for Aoj {
     fn authenticate(&self, _quiet: bool) -> Result<()> {
-        // TODO: implement
+        let (username, password) = self.credentials.get("AOJ")?;
+        eprintln_tagged!("Logging in": "to AOJ");
+        auth::aoj::login(&username, &password)?;
+        eprintln_tagged!("Finished": "logging in; successfully saved");
+
         Ok(())
     }
 }
@@ -1,21 +1,67 @@
-use super::LoginUi;
-use crate::eprintln_tagged;
-use crate::imp::auth;
-use anyhow::{Context, Result};
+use super::{Credentials, LoginUi};
+use crate::imp::auth::{self, SessionStatus};
+use crate::{eprintln_tagged, eprintln_warning};
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::io::{self, Read};
 
 #[derive(clap::Parser)]
 #[clap(about = "Logs in to AtCoder")]
-pub struct AtCoder;
+pub struct AtCoder {
+    #[clap(flatten)]
+    credentials: Credentials,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = &["username", "username_env", "password_env", "password_stdin"],
+        help = "Imports REVEL_SESSION exported from the browser (a cookies.txt, `REVEL_SESSION=...` or the value itself; `-` for stdin) instead of logging in"
+    )]
+    import_session: Option<String>,
+}
 
 impl AtCoder {
     pub fn run(self, quiet: bool) -> Result<()> {
-        AtCoder.authenticate(quiet).context("failed to login")
+        self.authenticate(quiet).context("failed to login")
+    }
+
+    fn import_session(&self, path: &str) -> Result<()> {
+        let text = if path == "-" {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .context("failed to read from stdin")?;
+            text
+        } else {
+            fs::read_to_string(path).with_context(|| format!("failed to read `{}`", path))?
+        };
+        let session = auth::atcoder::parse_exported_session(&text)
+            .ok_or_else(|| anyhow!("REVEL_SESSION is not found"))?;
+
+        auth::atcoder::import_session(&session)?;
+        match auth::atcoder::session_status() {
+            Ok(SessionStatus::LoggedIn { username }) => {
+                eprintln_tagged!("Finished": "importing the session; logged in as {}", username);
+            }
+            Ok(_) => {
+                eprintln_warning!("imported the session, but it is not accepted by AtCoder");
+            }
+            Err(e) => {
+                eprintln_warning!("imported the session, but failed to check it: {:#}", e);
+            }
+        }
+
+        Ok(())
     }
 }
 
 impl LoginUi for AtCoder {
     fn authenticate(&self, _quiet: bool) -> Result<()> {
-        let (username, password) = auth::ask_account_info("AtCoder");
+        if let Some(path) = &self.import_session {
+            return self.import_session(path);
+        }
+
+        let (username, password) = self.credentials.get("AtCoder")?;
         eprintln_tagged!("Logging in": "to AtCoder");
         auth::atcoder::login(&username, &password)?;
         eprintln_tagged!("Finished": "fetching code; successfully saved");
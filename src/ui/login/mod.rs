@@ -1,8 +1,12 @@
 pub mod aoj;
 pub mod atcoder;
+pub mod status;
 
+use crate::imp::auth;
 use crate::ExitStatus;
 use anyhow::{Context, Result};
+use std::env;
+use std::io::{self, BufRead};
 
 #[derive(clap::Parser)]
 #[clap(about = "Logs in to a contest-site")]
@@ -18,25 +22,89 @@ pub enum Site {
 
     #[clap(name = "aoj")]
     Aoj(aoj::Aoj),
+
+    #[clap(name = "status")]
+    Status(status::Status),
 }
 
 impl Site {
-    fn run(self, quiet: bool) -> Result<()> {
+    fn run(self, quiet: bool) -> Result<ExitStatus> {
         match self {
-            Site::AtCoder(cmd) => cmd.run(quiet).context("failed to login"),
-            Site::Aoj(cmd) => cmd.run(quiet).context("failed to login"),
+            Site::AtCoder(cmd) => cmd.run(quiet).context("failed to login")?,
+            Site::Aoj(cmd) => cmd.run(quiet).context("failed to login")?,
+            Site::Status(cmd) => return cmd.run(quiet).context("failed to get the login status"),
         }
+
+        Ok(ExitStatus::Success)
     }
 }
 
 impl Login {
     pub fn run(self, quiet: bool) -> Result<ExitStatus> {
-        self.site.run(quiet)?;
+        self.site.run(quiet)
+    }
+}
 
-        Ok(ExitStatus::Success)
+/// Where to get the account information from; asked on the terminal by
+/// default.
+#[derive(clap::Args)]
+#[clap(group(clap::ArgGroup::new("username_source").args(["username", "username_env"])))]
+pub struct Credentials {
+    #[clap(long, conflicts_with = "username_env", help = "The username")]
+    username: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "VAR",
+        help = "Reads the username from the environment variable"
+    )]
+    username_env: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "VAR",
+        conflicts_with = "password_stdin",
+        help = "Reads the password from the environment variable"
+    )]
+    password_env: Option<String>,
+
+    #[clap(
+        long,
+        requires = "username_source",
+        help = "Reads the password from the first line of the standard input"
+    )]
+    password_stdin: bool,
+}
+
+impl Credentials {
+    pub fn get(&self, service_name: &str) -> Result<(String, String)> {
+        let username = match (&self.username, &self.username_env) {
+            (Some(username), _) => username.clone(),
+            (None, Some(var)) => read_env(var)?,
+            (None, None) => auth::ask_username(service_name),
+        };
+
+        let password = if let Some(var) = &self.password_env {
+            read_env(var)?
+        } else if self.password_stdin {
+            let mut password = String::new();
+            io::stdin()
+                .lock()
+                .read_line(&mut password)
+                .context("failed to read the password from stdin")?;
+            password.trim_end_matches(['\r', '\n']).to_string()
+        } else {
+            auth::ask_password(service_name)
+        };
+
+        Ok((username, password))
     }
 }
 
+fn read_env(var: &str) -> Result<String> {
+    env::var(var).with_context(|| format!("failed to read the environment variable `{}`", var))
+}
+
 pub trait LoginUi {
     fn authenticate(&self, quiet: bool) -> Result<()>;
 }
@@ -0,0 +1,55 @@
+use crate::imp::auth::{self, SessionStatus};
+use crate::ExitStatus;
+use crate::{eprintln_error, eprintln_info, eprintln_tagged, eprintln_warning};
+use anyhow::Result;
+
+type CheckSession = fn() -> Result<SessionStatus>;
+
+#[derive(clap::Parser)]
+#[clap(about = "Shows whether the saved sessions are still valid")]
+pub struct Status;
+
+impl Status {
+    /// Fails if a saved session has expired or cannot be checked, so that
+    /// scripts can check it before a contest. Sites not logged in are fine.
+    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        let sites: [(&str, &str, CheckSession); 2] = [
+            ("AtCoder", "atcoder", auth::atcoder::session_status),
+            ("AOJ", "aoj", auth::aoj::session_status),
+        ];
+
+        let mut all_valid = true;
+        for (site_name, command, session_status) in sites {
+            let status = match session_status() {
+                Ok(status) => status,
+                Err(e) => {
+                    all_valid = false;
+                    eprintln_error!("failed to check the session of {}: {:#}", site_name, e);
+                    continue;
+                }
+            };
+            match status {
+                SessionStatus::LoggedIn { username } => {
+                    eprintln_tagged!("Logged in": "to {} as {}", site_name, username);
+                }
+                SessionStatus::Expired => {
+                    all_valid = false;
+                    eprintln_warning!(
+                        "the session of {} has expired; log in again with `login {}`",
+                        site_name,
+                        command
+                    );
+                }
+                SessionStatus::NotLoggedIn => {
+                    eprintln_info!("not logged in to {}", site_name);
+                }
+            }
+        }
+
+        Ok(if all_valid {
+            ExitStatus::Success
+        } else {
+            ExitStatus::Failure
+        })
+    }
+}
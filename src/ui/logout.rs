@@ -0,0 +1,27 @@
+use crate::eprintln_tagged;
+use crate::imp::auth;
+use crate::ExitStatus;
+use anyhow::bail;
+use anyhow::{Context, Result};
+
+#[derive(clap::Parser)]
+#[clap(about = "Logs out from a contest-site, removing the saved session")]
+pub struct Logout {
+    #[clap(help = "The contest-site to log out from. ex) atcoder, aoj")]
+    site: String,
+}
+
+impl Logout {
+    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        let (service_name, site_name) = match &*self.site {
+            "atcoder" | "at" => ("atcoder", "AtCoder"),
+            "aoj" => ("aoj", "AOJ"),
+            site => bail!("unknown contest site: `{}`", site),
+        };
+
+        auth::clear_session_info(service_name).context("failed to remove the session")?;
+        eprintln_tagged!("Logged out": "from {}", site_name);
+
+        Ok(ExitStatus::Success)
+    }
+}
@@ -0,0 +1,97 @@
+use crate::imp::case_info::CaseInfos;
+use crate::imp::test_case;
+use crate::ExitStatus;
+use crate::{eprintln_info, eprintln_warning};
+use anyhow::{Context, Result};
+use console::style;
+
+const PREVIEW_WIDTH: usize = 60;
+
+#[derive(clap::Parser)]
+#[clap(about = "Lists test cases with a preview of their contents")]
+pub struct LsCase {
+    #[clap(
+        short = 'n',
+        long,
+        default_value = "3",
+        help = "The number of lines to preview for each file"
+    )]
+    preview_lines: usize,
+}
+
+impl LsCase {
+    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        let test_cases =
+            test_case::enumerate_test_case_files().context("failed to enumerate test cases")?;
+        if test_cases.is_empty() {
+            eprintln_info!("no test cases found");
+            return Ok(ExitStatus::Success);
+        }
+
+        let infos = CaseInfos::load().unwrap_or_else(|e| {
+            eprintln_warning!("failed to load the information of test cases: {}", e);
+            CaseInfos::default()
+        });
+
+        for test_case in &test_cases {
+            let index = test_case.index();
+            let info = index.and_then(|idx| infos.get(idx));
+            let origin = info
+                .and_then(|info| info.origin)
+                .map_or_else(|| "unknown".to_string(), |origin| origin.to_string());
+            let verdict = match info.and_then(|info| info.verdict.as_deref()) {
+                Some("AC") => style("AC").green(),
+                Some(verdict) => style(verdict).yellow(),
+                None => style("-"),
+            };
+
+            let title = index.map_or_else(|| test_case.to_string(), |idx| format!("#{}", idx));
+            println!(
+                "{} (origin: {}, last verdict: {})",
+                style(title).bold(),
+                origin,
+                verdict
+            );
+            print_file(
+                &test_case.if_name,
+                &test_case.if_contents,
+                self.preview_lines,
+            );
+            print_file(
+                &test_case.of_name,
+                &test_case.of_contents,
+                self.preview_lines,
+            );
+        }
+
+        Ok(ExitStatus::Success)
+    }
+}
+
+fn print_file(name: &str, contents: &str, preview_lines: usize) {
+    let num_lines = contents.lines().count();
+    println!(
+        "    {} ({} bytes, {} lines)",
+        name,
+        contents.len(),
+        num_lines
+    );
+
+    for line in contents.lines().take(preview_lines) {
+        println!("      | {}", truncate(line));
+    }
+
+    if num_lines > preview_lines {
+        println!("      | ...");
+    }
+}
+
+fn truncate(line: &str) -> String {
+    if line.chars().count() <= PREVIEW_WIDTH {
+        return line.to_string();
+    }
+
+    let mut truncated: String = line.chars().take(PREVIEW_WIDTH).collect();
+    truncated.push_str("...");
+    truncated
+}
@@ -1,16 +1,23 @@
 mod addcase;
 mod clip;
+mod compact;
 mod compile;
 mod delcase;
 mod doc;
 mod download;
 mod fetch;
+mod genout;
 mod init;
 mod initdirs;
 mod login;
+mod logout;
+mod lscase;
+mod mvcase;
 mod open;
 mod preprocess;
 mod run;
+mod statement;
+mod swapcase;
 
 pub mod print_macros;
 
@@ -49,6 +56,21 @@ enum SubCommand {
     #[clap(name = "delcase", aliases = &["dc"])]
     DelCase(delcase::DelCase),
 
+    #[clap(name = "lscase", aliases = &["lc", "ls"])]
+    LsCase(lscase::LsCase),
+
+    #[clap(name = "mvcase", aliases = &["mc"])]
+    MvCase(mvcase::MvCase),
+
+    #[clap(name = "swapcase", aliases = &["sc"])]
+    SwapCase(swapcase::SwapCase),
+
+    #[clap(name = "compact", aliases = &["cc"])]
+    Compact(compact::Compact),
+
+    #[clap(name = "genout", aliases = &["g", "go"])]
+    GenOut(genout::GenOut),
+
     #[clap(name = "doc", aliases = &["do"])]
     Doc(doc::Doc),
 
@@ -64,6 +86,9 @@ enum SubCommand {
     #[clap(name = "download", aliases = &["d", "dl"])]
     Download(download::Download),
 
+    #[clap(name = "statement", aliases = &["st"])]
+    Statement(statement::Statement),
+
     #[clap(name = "run", aliases = &["r"])]
     Run(run::Run),
 
@@ -72,6 +97,9 @@ enum SubCommand {
 
     #[clap(name = "login", aliases = &["l"])]
     Login(login::Login),
+
+    #[clap(name = "logout")]
+    Logout(logout::Logout),
 }
 
 impl SubCommand {
@@ -82,14 +110,21 @@ impl SubCommand {
             SubCommand::Open(cmd) => cmd.run(quiet),
             SubCommand::AddCase(cmd) => cmd.run(quiet),
             SubCommand::DelCase(cmd) => cmd.run(quiet),
+            SubCommand::LsCase(cmd) => cmd.run(quiet),
+            SubCommand::MvCase(cmd) => cmd.run(quiet),
+            SubCommand::SwapCase(cmd) => cmd.run(quiet),
+            SubCommand::Compact(cmd) => cmd.run(quiet),
+            SubCommand::GenOut(cmd) => cmd.run(quiet),
             SubCommand::Doc(cmd) => cmd.run(quiet),
             SubCommand::Preprocess(cmd) => cmd.run(quiet),
             SubCommand::Clip(cmd) => cmd.run(quiet),
             SubCommand::Fetch(cmd) => cmd.run(quiet),
             SubCommand::Download(cmd) => cmd.run(quiet),
+            SubCommand::Statement(cmd) => cmd.run(quiet),
             SubCommand::Run(cmd) => cmd.run(quiet),
             SubCommand::Compile(cmd) => cmd.run(quiet),
             SubCommand::Login(cmd) => cmd.run(quiet),
+            SubCommand::Logout(cmd) => cmd.run(quiet),
         }
     }
 }
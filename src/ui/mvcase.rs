@@ -0,0 +1,23 @@
+use crate::eprintln_tagged;
+use crate::imp::test_case;
+use crate::ExitStatus;
+use anyhow::{Context, Result};
+
+#[derive(clap::Parser)]
+#[clap(about = "Moves a test case to the specified number, shifting the test cases in between")]
+pub struct MvCase {
+    #[clap(help = "The test case number to move")]
+    from: i32,
+
+    #[clap(help = "The new test case number")]
+    to: i32,
+}
+
+impl MvCase {
+    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        test_case::move_test_case(self.from, self.to).context("failed to move the test case")?;
+        eprintln_tagged!("Moved": "test case {} to {}", self.from, self.to);
+
+        Ok(ExitStatus::Success)
+    }
+}
@@ -1,6 +1,8 @@
+use crate::imp::case_info::CaseInfos;
 use crate::imp::config::CONFIG;
 use crate::imp::langs;
 use crate::imp::langs::Lang;
+use crate::imp::problem_info::ProblemInfo;
 use crate::imp::test_case;
 use crate::imp::test_case::{
     Accepted, Context, JudgeResult, PresentationError, RuntimeError, Span, TestCase, TestCaseFile,
@@ -38,7 +40,7 @@ pub struct Run {
     #[clap(
         short,
         long = "timeout",
-        help = "Override default timeout milliseconds in config.json"
+        help = "Override the timeout milliseconds; the time limit of the problem (or config.json) by default"
     )]
     timeout_milliseconds: Option<String>,
     #[clap(help = "Test case IDs to test")]
@@ -63,6 +65,13 @@ impl Run {
 
         let status = compile::compile(quiet, self.release_compile, &*lang, self.force_compile)
             .context("failed to compile")?;
+        let info = ProblemInfo::load().unwrap_or_else(|e| {
+            eprintln_warning!("failed to load the problem information: {}", e);
+            None
+        });
+        if let Some(info) = &info {
+            warn_judge_kind(info);
+        }
         let timeout_milliseconds = self
             .timeout_milliseconds
             .map(|timeout| {
@@ -75,7 +84,7 @@ impl Run {
                         .context("failed to parse timeout milliseconds")
                 }
             })
-            .unwrap_or_else(|| Ok(Some(CONFIG.run.timeout_milliseconds)))?;
+            .unwrap_or_else(|| Ok(Some(default_timeout_milliseconds(&info))))?;
         let timeout = timeout_milliseconds.map(time::Duration::from_millis);
 
         let result = if status == ExitStatus::Success {
@@ -102,6 +111,24 @@ impl Run {
     }
 }
 
+fn default_timeout_milliseconds(info: &Option<ProblemInfo>) -> u64 {
+    info.as_ref()
+        .and_then(|info| info.time_limit_milliseconds)
+        .unwrap_or(CONFIG.run.timeout_milliseconds)
+}
+
+/// Warns when the verdict of this command may not be reliable.
+fn warn_judge_kind(info: &ProblemInfo) {
+    if info.interactive {
+        eprintln_warning!("this problem seems interactive; the samples may not be judged properly");
+    }
+    if info.special_judge {
+        eprintln_warning!(
+            "this problem seems to accept several outputs; WA may not mean a wrong answer"
+        );
+    }
+}
+
 fn run_tests<L: Lang + ?Sized>(
     quiet: bool,
     release: bool,
@@ -172,16 +199,22 @@ fn run<L: Lang + ?Sized>(
                 lang.run_command()?
             };
 
+            let index = match &tc {
+                TestCase::File(f) => f.index(),
+                TestCase::Stdin(_) => None,
+            };
+
             Ok(thread::spawn(move || {
-                (tc.to_string(), tc.judge(cmd, timeout))
+                (index, tc.to_string(), tc.judge(cmd, timeout))
             }))
         })
         .collect::<Result<Vec<_>>>()?; // needs collect to spawn judge
 
     eprintln!();
     let mut whole_result = TestResult::Accepted(Accepted::new_empty());
+    let mut verdicts = Vec::new();
     for handle in handles {
-        let (display, result) = handle
+        let (index, display, result) = handle
             .join()
             .map_err(|_| anyhow!("judge thread panicked"))
             .context("failed to judge")?;
@@ -190,14 +223,28 @@ fn run<L: Lang + ?Sized>(
 
         // update the whole result
         let result = result.result;
+        verdicts.extend(index.map(|index| (index, result.short_name())));
         if result.is_failed() && whole_result.is_accepted() {
             whole_result = result;
         }
     }
 
+    if let Err(e) = record_verdicts(&verdicts) {
+        eprintln_warning!("failed to record verdicts: {}", e);
+    }
+
     Ok(whole_result)
 }
 
+fn record_verdicts(verdicts: &[(i32, &str)]) -> Result<()> {
+    let mut infos = CaseInfos::load()?;
+    for &(index, verdict) in verdicts {
+        infos.set_verdict(index, verdict.to_string());
+    }
+
+    infos.save()
+}
+
 fn print_result(quiet: bool, result: &JudgeResult, display: String) {
     let JudgeResult { result, elapsed } = result;
 
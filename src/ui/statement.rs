@@ -0,0 +1,39 @@
+use crate::imp::{process, statement};
+use crate::ExitStatus;
+use anyhow::ensure;
+use anyhow::{Context, Result};
+use std::fs;
+
+#[derive(clap::Parser)]
+#[clap(about = "Shows the problem statement saved by `fetch` or `download`")]
+pub struct Statement {
+    #[clap(
+        short,
+        long,
+        help = "Opens the statement in the browser instead of printing it"
+    )]
+    browser: bool,
+}
+
+impl Statement {
+    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        let path = statement::path();
+        ensure!(
+            path.exists(),
+            "no statement is saved; fetch the problem first"
+        );
+
+        if self.browser {
+            let path = fs::canonicalize(&path)
+                .with_context(|| format!("failed to get the full path of `{}`", path.display()))?;
+            process::open_browser(&path.display().to_string())
+                .context("failed to start browser")?;
+        } else {
+            let html = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read `{}`", path.display()))?;
+            print!("{}", statement::render_text(&html));
+        }
+
+        Ok(ExitStatus::Success)
+    }
+}
@@ -0,0 +1,23 @@
+use crate::eprintln_tagged;
+use crate::imp::test_case;
+use crate::ExitStatus;
+use anyhow::{Context, Result};
+
+#[derive(clap::Parser)]
+#[clap(about = "Swaps two test cases")]
+pub struct SwapCase {
+    #[clap(help = "The test case number to swap")]
+    a: i32,
+
+    #[clap(help = "The other test case number to swap")]
+    b: i32,
+}
+
+impl SwapCase {
+    pub fn run(self, _quiet: bool) -> Result<ExitStatus> {
+        test_case::swap_test_cases(self.a, self.b).context("failed to swap the test cases")?;
+        eprintln_tagged!("Swapped": "test case {} and {}", self.a, self.b);
+
+        Ok(ExitStatus::Success)
+    }
+}